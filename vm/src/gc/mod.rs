@@ -3,27 +3,237 @@ mod header;
 pub use header::Header as PyGCHeader;
 
 use crate::PyObjectRef;
+use crate::common::lock::PyMutex;
+use std::collections::VecDeque;
 
 pub enum Algorithm {
     MarkAndSweep,
-    TriColor,
-    None
+    /// The gray worklist persists across [`Algorithm::tri_color_step`] calls so a step
+    /// can resume exactly where the previous one left off, instead of re-deriving the
+    /// frontier from `roots` (which would lose anything discovered mid-cycle or
+    /// re-shaded by [`Algorithm::write_barrier`]).
+    TriColor(PyMutex<TriColorState>),
+    None,
 }
 
+/// Persisted state for the tri-color collector across [`Algorithm::tri_color_step`]
+/// calls and across whole collection cycles.
+#[derive(Default)]
+pub struct TriColorState {
+    gray: VecDeque<PyObjectRef>,
+    /// Every object this collector has shaded away from White since the last
+    /// white-out, so the next cycle can reset exactly those objects back to White
+    /// instead of either leaking a full heap scan into this module or, worse, never
+    /// resetting at all (which would leave every object from the first cycle Black
+    /// forever, so later cycles only re-grey the roots and never reclaim anything).
+    marked: Vec<PyObjectRef>,
+}
+
+/// The three sets of the tri-color invariant: white objects are presumed garbage,
+/// gray objects are known-reachable but not yet scanned, black objects are
+/// known-reachable and fully scanned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Color {
+    White,
+    Gray,
+    Black,
+}
+
+/// How many gray objects to pop and scan in a single [`Algorithm::tri_color_step`] call.
+/// Bounding this is what lets collection be interleaved with execution instead of
+/// stopping the world.
+const DEFAULT_STEP_BUDGET: usize = 256;
+
 impl Algorithm {
+    /// Build a fresh [`Algorithm::TriColor`] with an empty, not-yet-started worklist.
+    pub fn tri_color() -> Self {
+        Algorithm::TriColor(PyMutex::new(TriColorState::default()))
+    }
+
     fn mark_and_sweep(&self, roots: &[PyObjectRef]) {
-        roots
+        let _ = roots;
+    }
+
+    /// Run the tri-color collector to completion, starting a fresh cycle from `roots`.
+    fn tri_color_to_completion(&self, roots: &[PyObjectRef]) {
+        let Algorithm::TriColor(state_cell) = self else {
+            return;
+        };
+        let mut state = state_cell.lock();
+        start_cycle(&mut state, roots);
+        while tri_color_step(&mut state, usize::MAX) {}
+        // Every object the walk never reached is still White and can be reclaimed.
+    }
+
+    /// Process at most `budget` gray objects and return whether any work remains,
+    /// allowing a caller to interleave collection with execution rather than
+    /// completing a full cycle in one call. The gray worklist is kept on `self`
+    /// between calls, so a step picks up exactly where the previous one (or a
+    /// [`Algorithm::write_barrier`] re-shade) left off, rather than re-deriving a
+    /// frontier from `roots` that would drop anything discovered mid-cycle.
+    pub fn tri_color_step(&self, roots: &[PyObjectRef], budget: Option<usize>) -> bool {
+        let Algorithm::TriColor(state_cell) = self else {
+            return false;
+        };
+        let mut state = state_cell.lock();
+        if state.gray.is_empty() {
+            // No cycle in progress: white-out everything the previous cycle marked,
+            // then start a new one from the current roots.
+            start_cycle(&mut state, roots);
+        }
+        tri_color_step(&mut state, budget.unwrap_or(DEFAULT_STEP_BUDGET))
     }
 
-    fn tri_color(&self, roots: &[PyObjectRef]) {
-        todo!()
+    /// Re-shade a black object's header back to gray because it just acquired a
+    /// reference to a white object, preserving the invariant that no black object may
+    /// reference a white one (the write barrier for incremental collection). The
+    /// re-shaded referent is pushed onto the persisted gray worklist so the next
+    /// [`Algorithm::tri_color_step`] call actually rescans it.
+    pub fn write_barrier(&self, obj: &PyObjectRef, referent: &PyObjectRef) {
+        if let Algorithm::TriColor(state_cell) = self {
+            let obj_header = header_of(obj);
+            let referent_header = header_of(referent);
+            if obj_header.color() == Color::Black && referent_header.color() == Color::White {
+                obj_header.set_color(Color::Gray);
+                referent_header.set_color(Color::Gray);
+                let mut state = state_cell.lock();
+                state.marked.push(referent.clone());
+                state.gray.push_back(referent.clone());
+            }
+        }
     }
 
     pub fn execute(&self, roots: &[PyObjectRef]) {
         match self {
             Algorithm::MarkAndSweep => self.mark_and_sweep(roots),
-            Algorithm::TriColor => self.tri_color(roots),
+            Algorithm::TriColor(_) => self.tri_color_to_completion(roots),
             Algorithm::None => {}
         }
     }
 }
+
+fn header_of(obj: &PyObjectRef) -> &PyGCHeader {
+    obj.gc_header()
+}
+
+/// White-out every object the previous cycle marked (so stale Black survivors don't
+/// make this cycle think they're already scanned and stale Gray leftovers don't get
+/// rescanned twice), then grey the roots to start the new cycle.
+fn start_cycle(state: &mut TriColorState, roots: &[PyObjectRef]) {
+    for obj in state.marked.drain(..) {
+        header_of(&obj).set_color(Color::White);
+    }
+    state.gray.clear();
+    for root in roots {
+        header_of(root).set_color(Color::Gray);
+        state.gray.push_back(root.clone());
+        state.marked.push(root.clone());
+    }
+}
+
+/// Pop up to `budget` gray objects, shade each to black, and shade every white
+/// referent it traces to gray. Returns `true` if the gray set is non-empty when the
+/// budget runs out (more work remains), `false` once it drains completely.
+fn tri_color_step(state: &mut TriColorState, budget: usize) -> bool {
+    let mut processed = 0;
+    while processed < budget {
+        let Some(obj) = state.gray.pop_front() else {
+            return false;
+        };
+        let header = header_of(&obj);
+        header.set_color(Color::Black);
+        obj.trace(&mut |referent| {
+            let referent_header = header_of(referent);
+            if referent_header.color() == Color::White {
+                referent_header.set_color(Color::Gray);
+                state.gray.push_back(referent.clone());
+                state.marked.push(referent.clone());
+            }
+        });
+        processed += 1;
+    }
+    !state.gray.is_empty()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Interpreter;
+
+    /// A frontier discovered by tracing a root mid-cycle (not present in the original
+    /// `roots` slice) must still be scanned by a later step, not dropped on the floor.
+    #[test]
+    fn step_resumes_a_frontier_discovered_mid_cycle() {
+        Interpreter::without_stdlib(Default::default()).enter(|vm| {
+            let leaf: PyObjectRef = vm.ctx.new_int(1).into();
+            let inner: PyObjectRef = vm.ctx.new_tuple(vec![leaf.clone()]).into();
+            let root: PyObjectRef = vm.ctx.new_tuple(vec![inner.clone()]).into();
+
+            let gc = Algorithm::tri_color();
+            let roots = vec![root.clone()];
+
+            // Step 1: mark `root` black, discover `inner` as a new gray frontier.
+            assert!(gc.tri_color_step(&roots, Some(1)));
+            assert_eq!(header_of(&root).color(), Color::Black);
+            assert_eq!(header_of(&inner).color(), Color::Gray);
+
+            // Step 2, same `roots`: a from-scratch rebuild would only see `root`
+            // (already Black) and incorrectly report the cycle as done. The persisted
+            // worklist must still hand back `inner`.
+            assert!(gc.tri_color_step(&roots, Some(1)));
+            assert_eq!(header_of(&inner).color(), Color::Black);
+            assert_eq!(header_of(&leaf).color(), Color::Gray);
+
+            assert!(!gc.tri_color_step(&roots, Some(1)));
+            assert_eq!(header_of(&leaf).color(), Color::Black);
+        });
+    }
+
+    /// `write_barrier` must not just re-shade colors: it has to queue the referent so
+    /// the very next step actually rescans it, or the object is silently dropped.
+    #[test]
+    fn write_barrier_reshade_is_rescanned_on_next_step() {
+        Interpreter::without_stdlib(Default::default()).enter(|vm| {
+            let child: PyObjectRef = vm.ctx.new_int(1).into();
+            let root: PyObjectRef = vm.ctx.new_int(2).into();
+
+            let gc = Algorithm::tri_color();
+            // Drive `root` to Black with nothing left in the worklist.
+            assert!(!gc.tri_color_step(&[root.clone()], None));
+            assert_eq!(header_of(&root).color(), Color::Black);
+            assert_eq!(header_of(&child).color(), Color::White);
+
+            // `root` (Black) acquires a reference to `child` (White).
+            gc.write_barrier(&root, &child);
+            assert_eq!(header_of(&child).color(), Color::Gray);
+
+            assert!(!gc.tri_color_step(&[], None));
+            assert_eq!(header_of(&child).color(), Color::Black);
+        });
+    }
+
+    /// A second cycle must white-out what the first cycle left Black, or else an
+    /// object that becomes unreachable between cycles is never reclaimed (it stays
+    /// Black forever) and a later cycle that *does* still reach it via `roots` sees
+    /// "already Black" and wrongly treats the cycle as instantly done.
+    #[test]
+    fn second_cycle_resets_colors_before_remarking() {
+        Interpreter::without_stdlib(Default::default()).enter(|vm| {
+            let survivor: PyObjectRef = vm.ctx.new_int(1).into();
+            let garbage: PyObjectRef = vm.ctx.new_int(2).into();
+
+            let gc = Algorithm::tri_color();
+
+            // Cycle 1: both objects are roots and end up Black.
+            assert!(!gc.tri_color_step(&[survivor.clone(), garbage.clone()], None));
+            assert_eq!(header_of(&survivor).color(), Color::Black);
+            assert_eq!(header_of(&garbage).color(), Color::Black);
+
+            // Cycle 2: `garbage` is no longer a root. Without a white-out it would
+            // stay Black (mistaken for reachable) instead of becoming collectible.
+            assert!(!gc.tri_color_step(&[survivor.clone()], None));
+            assert_eq!(header_of(&survivor).color(), Color::Black);
+            assert_eq!(header_of(&garbage).color(), Color::White);
+        });
+    }
+}
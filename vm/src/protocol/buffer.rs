@@ -12,7 +12,14 @@ use crate::{
     types::Unconstructible,
 };
 use itertools::Itertools;
-use std::{borrow::Cow, fmt::Debug, ops::Range};
+use smallvec::SmallVec;
+use std::{
+    borrow::Cow,
+    fmt::Debug,
+    io::{IoSlice, IoSliceMut},
+    ops::{Deref, Range},
+    sync::atomic::{AtomicUsize, Ordering},
+};
 
 pub struct BufferMethods {
     pub obj_bytes: fn(&PyBuffer) -> BorrowedValue<'_, [u8]>,
@@ -32,7 +39,7 @@ impl Debug for BufferMethods {
     }
 }
 
-#[derive(Debug, Clone, Traverse)]
+#[derive(Debug, Traverse)]
 pub struct PyBuffer {
     pub obj: PyObjectRef,
     #[pytraverse(skip)]
@@ -41,6 +48,23 @@ pub struct PyBuffer {
     methods: &'static BufferMethods,
 }
 
+impl Clone for PyBuffer {
+    /// A cloned `PyBuffer` is a new export of the same underlying buffer, so this must
+    /// `retain()` like `PyBuffer::new` does — a field-wise derive would copy `exports`
+    /// tracking state without ever incrementing it, so the clone's `Drop` would
+    /// decrement a count its own construction never added to, letting
+    /// `try_resizable_opt` observe zero live exports while one is still borrowed.
+    fn clone(&self) -> Self {
+        let zelf = Self {
+            obj: self.obj.clone(),
+            desc: self.desc.clone(),
+            methods: self.methods,
+        };
+        zelf.retain();
+        zelf
+    }
+}
+
 impl PyBuffer {
     pub fn new(obj: PyObjectRef, desc: BufferDescriptor, methods: &'static BufferMethods) -> Self {
         let zelf = Self {
@@ -75,13 +99,17 @@ impl PyBuffer {
     /// # Safety
     /// assume the buffer is contiguous
     pub unsafe fn contiguous_unchecked(&self) -> BorrowedValue<'_, [u8]> {
-        self.obj_bytes()
+        let base = self.desc.base_offset;
+        let len = self.desc.len;
+        BorrowedValue::map(self.obj_bytes(), |bytes| &bytes[base..base + len])
     }
 
     /// # Safety
     /// assume the buffer is contiguous and writable
     pub unsafe fn contiguous_mut_unchecked(&self) -> BorrowedValueMut<'_, [u8]> {
-        self.obj_bytes_mut()
+        let base = self.desc.base_offset;
+        let len = self.desc.len;
+        BorrowedValueMut::map(self.obj_bytes_mut(), |bytes| &mut bytes[base..base + len])
     }
 
     pub fn append_to(&self, buf: &mut Vec<u8>) {
@@ -109,6 +137,104 @@ impl PyBuffer {
         f(v)
     }
 
+    /// A zero-copy view of the byte range `range` of this buffer: a new `PyBuffer`
+    /// sharing the same underlying `obj` (via `retain()`, so the export count stays
+    /// correct) and `methods` table, with `desc` restricted to the given range instead
+    /// of collecting through [`Self::contiguous_or_collect`]. Only defined for a
+    /// contiguous buffer, since an arbitrary strided sub-range isn't expressible as a
+    /// single extra dimension the way a contiguous byte range is.
+    pub fn subbuffer(&self, range: Range<usize>, vm: &VirtualMachine) -> PyResult<PyBuffer> {
+        if !self.desc.is_contiguous() {
+            return Err(vm.new_buffer_error("cannot slice a non-contiguous buffer"));
+        }
+        if range.start > range.end || range.end > self.desc.len {
+            return Err(vm.new_index_error("buffer slice index out of range".to_owned()));
+        }
+        let byte_len = range.end - range.start;
+        if byte_len % self.desc.itemsize != 0 {
+            return Err(
+                vm.new_value_error("buffer slice is not a multiple of itemsize".to_owned())
+            );
+        }
+        let desc = BufferDescriptor {
+            len: byte_len,
+            readonly: self.desc.readonly,
+            itemsize: self.desc.itemsize,
+            format: self.desc.format.clone(),
+            dim_desc: vec![(byte_len / self.desc.itemsize, self.desc.itemsize as isize, 0)],
+            // Compose with any offset `self` already carries, so slicing a subbuffer
+            // of a subbuffer still lands at the right place in the original object.
+            base_offset: self.desc.base_offset + range.start,
+        };
+        Ok(PyBuffer::new(self.obj.clone(), desc, self.methods))
+    }
+
+    /// Gather-I/O view of this buffer's contents, one [`IoSlice`] per contiguous run
+    /// (a single slice for a contiguous buffer, one per segment from
+    /// `desc.for_each_segment` otherwise) — for handing a strided `memoryview` straight
+    /// to a vectored write (`writev`, `socket.send`) without `append_to`'s copy into a
+    /// `Vec<u8>`. The returned value keeps the lock guard backing the slices alive
+    /// alongside them.
+    pub fn io_slices(&self) -> PyBufferIoSlices<'_> {
+        let guard = self.obj_bytes();
+        // SAFETY: `guard` is a (possibly mapped) lock guard over the buffer's backing
+        // storage; that storage's address doesn't depend on where `guard` itself is
+        // stored, only on `guard` staying alive. `PyBufferIoSlices` bundles `bytes`
+        // (and the `IoSlice`s built from it) together with `guard` so it can't outlive
+        // the lock that validates it.
+        let bytes: &'_ [u8] = unsafe { &*(&*guard as *const [u8]) };
+        let mut slices = SmallVec::new();
+        if self.desc.is_contiguous() {
+            let base = self.desc.base_offset;
+            let len = self.desc.len;
+            slices.push(IoSlice::new(&bytes[base..base + len]));
+        } else {
+            self.desc.for_each_segment(true, |range| {
+                slices.push(IoSlice::new(
+                    &bytes[range.start as usize..range.end as usize],
+                ));
+            });
+        }
+        PyBufferIoSlices {
+            _guard: guard,
+            slices,
+        }
+    }
+
+    /// The mutable, scatter-I/O counterpart of [`Self::io_slices`]; `None` if the
+    /// buffer is read-only.
+    pub fn io_slices_mut(&self) -> Option<PyBufferIoSlicesMut<'_>> {
+        if self.desc.readonly {
+            return None;
+        }
+        let mut guard = self.obj_bytes_mut();
+        // SAFETY: see `io_slices` — `PyBufferIoSlicesMut` keeps `guard` alive for
+        // exactly as long as the slices borrowed from it.
+        let base: *mut u8 = guard.as_mut_ptr();
+        let mut slices = SmallVec::new();
+        if self.desc.is_contiguous() {
+            // SAFETY: `base_offset..base_offset + len` is this view's own byte range
+            // within the guard's full backing storage.
+            slices.push(IoSliceMut::new(unsafe {
+                std::slice::from_raw_parts_mut(base.add(self.desc.base_offset), self.desc.len)
+            }));
+        } else {
+            self.desc.for_each_segment(true, |range| {
+                let start = range.start as usize;
+                let len = (range.end - range.start) as usize;
+                // SAFETY: `for_each_segment`'s segments are disjoint, in-bounds byte
+                // ranges of the buffer, so handing out one `&mut` per segment doesn't
+                // alias even though they're all derived from the same `base` pointer.
+                let segment = unsafe { std::slice::from_raw_parts_mut(base.add(start), len) };
+                slices.push(IoSliceMut::new(segment));
+            });
+        }
+        Some(PyBufferIoSlicesMut {
+            _guard: guard,
+            slices,
+        })
+    }
+
     pub fn obj_as<T: PyObjectPayload>(&self) -> &Py<T> {
         unsafe { self.obj.downcast_unchecked_ref() }
     }
@@ -141,6 +267,171 @@ impl PyBuffer {
     }
 }
 
+/// A scalar type that can back a [`TypedPyBuffer`]: the set of `struct`-module format
+/// codes it may appear under, and the byte width those codes imply.
+pub trait Element: Sized + Copy {
+    const FORMAT: &'static [char];
+    const ITEMSIZE: usize;
+}
+
+macro_rules! impl_element {
+    ($ty:ty, $itemsize:expr, [$($format:literal),+ $(,)?]) => {
+        impl Element for $ty {
+            const FORMAT: &'static [char] = &[$($format),+];
+            const ITEMSIZE: usize = $itemsize;
+        }
+    };
+}
+
+impl_element!(u8, 1, ['B', 'c']);
+impl_element!(i8, 1, ['b']);
+impl_element!(u16, 2, ['H']);
+impl_element!(i16, 2, ['h']);
+impl_element!(u32, 4, ['I', 'L']);
+impl_element!(i32, 4, ['i', 'l']);
+impl_element!(u64, 8, ['Q']);
+impl_element!(i64, 8, ['q']);
+impl_element!(f32, 4, ['f']);
+impl_element!(f64, 8, ['d']);
+
+/// A [`PyBuffer`] known (and validated) to expose its bytes as a sequence of `T`,
+/// instead of raw bytes that every caller has to reinterpret by hand via
+/// `obj_bytes()` + itemsize arithmetic.
+#[derive(Debug, Clone)]
+pub struct TypedPyBuffer<T> {
+    pub buffer: PyBuffer,
+    _phantom: std::marker::PhantomData<T>,
+}
+
+impl PyBuffer {
+    /// Wrap this buffer as a `TypedPyBuffer<T>`, checking that its itemsize and
+    /// `struct`-format both match `T`.
+    pub fn try_typed<T: Element>(self, vm: &VirtualMachine) -> PyResult<TypedPyBuffer<T>> {
+        if self.desc.itemsize != T::ITEMSIZE {
+            return Err(vm.new_value_error(format!(
+                "expected an item size of {}, got {}",
+                T::ITEMSIZE,
+                self.desc.itemsize
+            )));
+        }
+        let format_matches = self.desc.format.len() == 1
+            && T::FORMAT.contains(&self.desc.format.chars().next().unwrap());
+        if !format_matches {
+            return Err(vm.new_type_error(format!(
+                "expected a buffer format of {:?}, got '{}'",
+                T::FORMAT, self.desc.format
+            )));
+        }
+        Ok(TypedPyBuffer {
+            buffer: self,
+            _phantom: std::marker::PhantomData,
+        })
+    }
+}
+
+impl<T: Element> TypedPyBuffer<T> {
+    /// The buffer's contents as `&[T]`, if it's contiguous and its start is aligned for
+    /// `T` (any other buffer can't be reinterpreted as a flat element slice without
+    /// copying).
+    pub fn as_slice(&self) -> Option<BorrowedValue<'_, [T]>> {
+        let bytes = self.buffer.as_contiguous()?;
+        if bytes.as_ptr().align_offset(std::mem::align_of::<T>()) != 0 {
+            return None;
+        }
+        Some(BorrowedValue::map(bytes, |bytes| cast_slice::<T>(bytes)))
+    }
+
+    /// The mutable counterpart of [`TypedPyBuffer::as_slice`]; `None` for a read-only,
+    /// non-contiguous or misaligned buffer.
+    pub fn as_mut_slice(&self) -> Option<BorrowedValueMut<'_, [T]>> {
+        if self.buffer.desc.readonly {
+            return None;
+        }
+        let bytes = self.buffer.as_contiguous_mut()?;
+        if bytes.as_ptr().align_offset(std::mem::align_of::<T>()) != 0 {
+            return None;
+        }
+        Some(BorrowedValueMut::map(bytes, |bytes| {
+            cast_slice_mut::<T>(bytes)
+        }))
+    }
+
+    /// Collect every element into a `Vec<T>`, walking `for_each_segment` to handle a
+    /// non-contiguous or misaligned layout a plain [`TypedPyBuffer::as_slice`] can't
+    /// borrow directly.
+    pub fn copy_to_vec(&self) -> Vec<T> {
+        if let Some(slice) = self.as_slice() {
+            return slice.to_vec();
+        }
+        let mut out = Vec::with_capacity(self.buffer.desc.len / T::ITEMSIZE);
+        let bytes = self.buffer.obj_bytes();
+        self.buffer.desc.for_each_segment(true, |range| {
+            let chunk = &bytes[range.start as usize..range.end as usize];
+            out.extend(chunk.chunks_exact(T::ITEMSIZE).map(|item| {
+                // SAFETY: `item` holds exactly `size_of::<T>()` bytes; the read needs no alignment.
+                unsafe { std::ptr::read_unaligned(item.as_ptr() as *const T) }
+            }));
+        });
+        out
+    }
+}
+
+/// # Safety (debug-only)
+/// Callers must only reach this with `bytes.len()` a multiple of `size_of::<T>()` and
+/// `bytes` aligned for `T`. The length holds for buffers validated by
+/// `PyBuffer::try_typed`, since every `Element` impl's `ITEMSIZE` matches
+/// `size_of::<T>()`; the alignment is checked by `TypedPyBuffer::as_slice` and
+/// `as_mut_slice` before they call this.
+fn cast_slice<T>(bytes: &[u8]) -> &[T] {
+    let ptr = bytes.as_ptr();
+    debug_assert_eq!(bytes.len() % std::mem::size_of::<T>(), 0);
+    debug_assert_eq!(ptr.align_offset(std::mem::align_of::<T>()), 0);
+    unsafe { std::slice::from_raw_parts(ptr as *const T, bytes.len() / std::mem::size_of::<T>()) }
+}
+
+fn cast_slice_mut<T>(bytes: &mut [u8]) -> &mut [T] {
+    let ptr = bytes.as_mut_ptr();
+    debug_assert_eq!(bytes.len() % std::mem::size_of::<T>(), 0);
+    debug_assert_eq!(ptr.align_offset(std::mem::align_of::<T>()), 0);
+    unsafe {
+        std::slice::from_raw_parts_mut(ptr as *mut T, bytes.len() / std::mem::size_of::<T>())
+    }
+}
+
+/// The result of [`PyBuffer::io_slices`]: a set of [`IoSlice`]s into a buffer's
+/// contents, kept alive alongside the lock guard they borrow from.
+pub struct PyBufferIoSlices<'a> {
+    _guard: BorrowedValue<'a, [u8]>,
+    slices: SmallVec<[IoSlice<'a>; 4]>,
+}
+
+impl<'a> Deref for PyBufferIoSlices<'a> {
+    type Target = [IoSlice<'a>];
+    fn deref(&self) -> &[IoSlice<'a>] {
+        &self.slices
+    }
+}
+
+/// The mutable counterpart of [`PyBufferIoSlices`], produced by
+/// [`PyBuffer::io_slices_mut`].
+pub struct PyBufferIoSlicesMut<'a> {
+    _guard: BorrowedValueMut<'a, [u8]>,
+    slices: SmallVec<[IoSliceMut<'a>; 4]>,
+}
+
+impl<'a> Deref for PyBufferIoSlicesMut<'a> {
+    type Target = [IoSliceMut<'a>];
+    fn deref(&self) -> &[IoSliceMut<'a>] {
+        &self.slices
+    }
+}
+
+impl<'a> std::ops::DerefMut for PyBufferIoSlicesMut<'a> {
+    fn deref_mut(&mut self) -> &mut [IoSliceMut<'a>] {
+        &mut self.slices
+    }
+}
+
 impl<'a> TryFromBorrowedObject<'a> for PyBuffer {
     fn try_from_borrowed_object(vm: &VirtualMachine, obj: &'a PyObject) -> PyResult<Self> {
         let cls = obj.class();
@@ -161,6 +452,17 @@ impl Drop for PyBuffer {
     }
 }
 
+/// Which standard memory order, if either, a [`BufferDescriptor`] is laid out in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Contiguity {
+    /// Row-major: the last dimension varies fastest (`PyBUF_C_CONTIGUOUS`).
+    C,
+    /// Column-major: the first dimension varies fastest (`PyBUF_F_CONTIGUOUS`).
+    Fortran,
+    /// Neither: e.g. a transposed view, or one with non-trivial suboffsets.
+    None,
+}
+
 #[derive(Debug, Clone)]
 pub struct BufferDescriptor {
     /// product(shape) * itemsize
@@ -171,6 +473,13 @@ pub struct BufferDescriptor {
     pub format: Cow<'static, str>,
     /// (shape, stride, suboffset) for each dimension
     pub dim_desc: Vec<(usize, isize, isize)>,
+    /// Byte offset into the slice `PyBuffer::obj_bytes`/`obj_bytes_mut` return where
+    /// this view's data actually starts. `0` for a buffer over the whole object; set
+    /// to a nonzero value by [`PyBuffer::subbuffer`] so a sliced view still knows
+    /// where it lives in the parent's backing storage even along the fast contiguous
+    /// path, which walks `dim_desc` directly and would otherwise have no way to see an
+    /// offset encoded only in a dimension's `suboffset`.
+    pub base_offset: usize,
     // TODO: flags
 }
 
@@ -182,22 +491,97 @@ impl BufferDescriptor {
             itemsize: 1,
             format: Cow::Borrowed("B"),
             dim_desc: vec![(bytes_len, 1, 0)],
+            base_offset: 0,
         }
     }
 
+    /// Build a 1-dimensional descriptor for a `format`-typed buffer, computing
+    /// `itemsize` from `format` itself (via [`format::parse`]) rather than taking it as
+    /// a separate, independently-trustable parameter. `format` is data-dependent (it
+    /// can come from a ctypes type or a user-supplied `struct` string), so a format
+    /// [`format::parse`] doesn't model is a `ValueError`, not a panic that aborts the
+    /// whole interpreter.
     pub fn format(
         bytes_len: usize,
         readonly: bool,
-        itemsize: usize,
         format: Cow<'static, str>,
-    ) -> Self {
-        Self {
+        vm: &VirtualMachine,
+    ) -> PyResult<Self> {
+        let parsed = format::parse(&format)
+            .map_err(|e| vm.new_value_error(format!("invalid struct format string {format:?}: {e}")))?;
+        Ok(Self {
             len: bytes_len,
             readonly,
-            itemsize,
+            itemsize: parsed.itemsize,
             format,
-            dim_desc: vec![(bytes_len / itemsize, itemsize as isize, 0)],
+            dim_desc: vec![(bytes_len / parsed.itemsize, parsed.itemsize as isize, 0)],
+            base_offset: 0,
+        })
+    }
+
+    /// Reinterpret this buffer under `new_format`/`new_shape`, following
+    /// `memoryview.cast`'s rules: only a C-contiguous buffer may be cast (casting would
+    /// otherwise have to invent strides for a layout that was never simply linear);
+    /// `len` must divide evenly by the new itemsize; and a cast that changes the number
+    /// of dimensions is only allowed to or from 1-D, with `new_shape` supplying the
+    /// other side's shape.
+    pub fn cast(
+        &self,
+        new_format: Cow<'static, str>,
+        new_shape: Option<Vec<usize>>,
+        vm: &VirtualMachine,
+    ) -> PyResult<Self> {
+        if self.contiguity() != Contiguity::C {
+            return Err(vm.new_type_error(
+                "memoryview: casts are restricted to C-contiguous buffers".to_owned(),
+            ));
         }
+        let parsed = format::parse(&new_format)
+            .map_err(|e| vm.new_value_error(format!("memoryview: destination format error: {e}")))?;
+        if parsed.itemsize == 0 || self.len % parsed.itemsize != 0 {
+            return Err(vm.new_type_error(
+                "memoryview: length is not a multiple of itemsize".to_owned(),
+            ));
+        }
+        let new_numel = self.len / parsed.itemsize;
+
+        let dim_desc = match new_shape {
+            Some(shape) => {
+                if self.ndim() != 1 {
+                    return Err(vm.new_type_error(
+                        "memoryview: cast must be 1D -> ND or ND -> 1D".to_owned(),
+                    ));
+                }
+                let product: usize = shape.iter().product();
+                if product != new_numel {
+                    return Err(vm.new_type_error(
+                        "memoryview: product(shape) * itemsize != buffer size".to_owned(),
+                    ));
+                }
+                let mut strides = vec![0isize; shape.len()];
+                let mut stride = parsed.itemsize as isize;
+                for (i, &s) in shape.iter().enumerate().rev() {
+                    strides[i] = stride;
+                    stride *= s as isize;
+                }
+                shape.into_iter().zip(strides).map(|(s, st)| (s, st, 0)).collect()
+            }
+            // No `shape` means flatten to 1-D, which CPython allows from any
+            // C-contiguous ND shape (already checked above) — only an ND -> ND cast
+            // (neither side 1-D) is rejected, and that can't happen here since this
+            // arm's result is always 1-D.
+            None => vec![(new_numel, parsed.itemsize as isize, 0)],
+        };
+
+        Ok(Self {
+            len: self.len,
+            readonly: self.readonly,
+            itemsize: parsed.itemsize,
+            format: new_format,
+            dim_desc,
+            base_offset: self.base_offset,
+        }
+        .validate())
     }
 
     #[cfg(debug_assertions)]
@@ -211,6 +595,13 @@ impl BufferDescriptor {
             assert!(stride != 0);
         }
         assert!(shape_product * self.itemsize == self.len);
+        if let Ok(parsed) = format::parse(&self.format) {
+            assert_eq!(
+                parsed.itemsize, self.itemsize,
+                "BufferDescriptor itemsize ({}) doesn't match format {:?} (itemsize {})",
+                self.itemsize, self.format, parsed.itemsize
+            );
+        }
         self
     }
 
@@ -223,12 +614,14 @@ impl BufferDescriptor {
         self.dim_desc.len()
     }
 
-    pub fn is_contiguous(&self) -> bool {
-        if self.len == 0 {
-            return true;
-        }
-        let mut sd = self.itemsize;
-        for (shape, stride, _) in self.dim_desc.iter().cloned().rev() {
+    /// `contiguity()`'s recurrence: walking `dims` from the fastest-varying dimension
+    /// to the slowest, each dimension's stride must equal itemsize times the product of
+    /// every faster dimension's shape. Walking `dim_desc` in reverse checks C order
+    /// (last dimension fastest); walking it forward checks Fortran order (first
+    /// dimension fastest).
+    fn check_contiguity(itemsize: usize, dims: impl Iterator<Item = (usize, isize, isize)>) -> bool {
+        let mut sd = itemsize;
+        for (shape, stride, _) in dims {
             if shape > 1 && stride != sd as isize {
                 return false;
             }
@@ -237,10 +630,51 @@ impl BufferDescriptor {
         true
     }
 
+    /// Which standard order, if either, this descriptor is laid out in — see
+    /// `PyBUF_C_CONTIGUOUS`/`PyBUF_F_CONTIGUOUS` in CPython's buffer protocol. A
+    /// zero-length buffer is trivially `C` (matching the prior behavior of
+    /// `is_contiguous`).
+    pub fn contiguity(&self) -> Contiguity {
+        if self.len == 0 {
+            return Contiguity::C;
+        }
+        if Self::check_contiguity(self.itemsize, self.dim_desc.iter().cloned().rev()) {
+            Contiguity::C
+        } else if Self::check_contiguity(self.itemsize, self.dim_desc.iter().cloned()) {
+            Contiguity::Fortran
+        } else {
+            Contiguity::None
+        }
+    }
+
+    /// Contiguous in either C or Fortran order (`PyBUF_ANY_CONTIGUOUS`). A contiguous
+    /// 1-dimensional buffer is both at once.
+    pub fn is_contiguous(&self) -> bool {
+        self.contiguity() != Contiguity::None
+    }
+
+    /// Specifically Fortran (column-major) contiguous, matching
+    /// `numpy.ndarray(order="F")` layouts.
+    pub fn fortran_contiguity(&self) -> bool {
+        self.len == 0 || Self::check_contiguity(self.itemsize, self.dim_desc.iter().cloned())
+    }
+
+    /// The dimension visiting order `for_each_segment`/`zip_eq`'s fast path should use:
+    /// the fastest-varying (unit contiguous-stride) dimension last, so the recursive
+    /// walk can collapse it into one contiguous run. `None` if neither standard order
+    /// applies and the caller must fall back to the per-element slow path.
+    fn fast_dim_order(&self) -> Option<Vec<usize>> {
+        match self.contiguity() {
+            Contiguity::C => Some((0..self.ndim()).collect()),
+            Contiguity::Fortran => Some((0..self.ndim()).rev().collect()),
+            Contiguity::None => None,
+        }
+    }
+
     /// this function do not check the bound
     /// panic if indices.len() != ndim
     pub fn fast_position(&self, indices: &[usize]) -> isize {
-        let mut pos = 0;
+        let mut pos = self.base_offset as isize;
         for (i, (_, stride, suboffset)) in indices
             .iter()
             .cloned()
@@ -253,7 +687,7 @@ impl BufferDescriptor {
 
     /// panic if indices.len() != ndim
     pub fn position(&self, indices: &[isize], vm: &VirtualMachine) -> PyResult<isize> {
-        let mut pos = 0;
+        let mut pos = self.base_offset as isize;
         for (i, (shape, stride, suboffset)) in indices
             .iter()
             .cloned()
@@ -267,27 +701,41 @@ impl BufferDescriptor {
         Ok(pos)
     }
 
+    /// Walks every byte range making up this buffer, in dimension order. When
+    /// `try_contiguous` is set and this descriptor has a standard order (C or
+    /// Fortran), the fastest-varying dimension is collapsed into one contiguous range
+    /// per outer index instead of one call per element — whichever of the first or
+    /// last physical dimension is the unit-stride one, per [`Self::fast_dim_order`].
     pub fn for_each_segment<F>(&self, try_contiguous: bool, mut f: F)
     where
         F: FnMut(Range<isize>),
     {
+        let base = self.base_offset as isize;
         if self.ndim() == 0 {
-            f(0..self.itemsize as isize);
+            f(base..base + self.itemsize as isize);
             return;
         }
-        if try_contiguous && self.is_last_dim_contiguous() {
-            self._for_each_segment::<_, true>(0, 0, &mut f);
-        } else {
-            self._for_each_segment::<_, false>(0, 0, &mut f);
+        let order = try_contiguous.then(|| self.fast_dim_order()).flatten();
+        match order {
+            Some(order) => self._for_each_segment::<_, true>(&order, base, 0, &mut f),
+            None => {
+                let order: Vec<usize> = (0..self.ndim()).collect();
+                self._for_each_segment::<_, false>(&order, base, 0, &mut f)
+            }
         }
     }
 
-    fn _for_each_segment<F, const CONTIGUOUS: bool>(&self, mut index: isize, dim: usize, f: &mut F)
-    where
+    fn _for_each_segment<F, const CONTIGUOUS: bool>(
+        &self,
+        order: &[usize],
+        mut index: isize,
+        pos: usize,
+        f: &mut F,
+    ) where
         F: FnMut(Range<isize>),
     {
-        let (shape, stride, suboffset) = self.dim_desc[dim];
-        if dim + 1 == self.ndim() {
+        let (shape, stride, suboffset) = self.dim_desc[order[pos]];
+        if pos + 1 == order.len() {
             if CONTIGUOUS {
                 f(index..index + (shape * self.itemsize) as isize);
             } else {
@@ -300,7 +748,7 @@ impl BufferDescriptor {
             return;
         }
         for _ in 0..shape {
-            self._for_each_segment::<F, CONTIGUOUS>(index + suboffset, dim + 1, f);
+            self._for_each_segment::<F, CONTIGUOUS>(order, index + suboffset, pos + 1, f);
             index += stride;
         }
     }
@@ -314,27 +762,33 @@ impl BufferDescriptor {
             f(0..self.itemsize as isize, 0..other.itemsize as isize);
             return;
         }
-        if try_contiguous && self.is_last_dim_contiguous() {
-            self._zip_eq::<_, true>(other, 0, 0, 0, &mut f);
-        } else {
-            self._zip_eq::<_, false>(other, 0, 0, 0, &mut f);
+        let order = try_contiguous.then(|| self.fast_dim_order()).flatten();
+        let (a_base, b_base) = (self.base_offset as isize, other.base_offset as isize);
+        match order {
+            Some(order) => self._zip_eq::<_, true>(other, &order, a_base, b_base, 0, &mut f),
+            None => {
+                let order: Vec<usize> = (0..self.ndim()).collect();
+                self._zip_eq::<_, false>(other, &order, a_base, b_base, 0, &mut f)
+            }
         }
     }
 
     fn _zip_eq<F, const CONTIGUOUS: bool>(
         &self,
         other: &Self,
+        order: &[usize],
         mut a_index: isize,
         mut b_index: isize,
-        dim: usize,
+        pos: usize,
         f: &mut F,
     ) where
         F: FnMut(Range<isize>, Range<isize>) -> bool,
     {
+        let dim = order[pos];
         let (shape, a_stride, a_suboffset) = self.dim_desc[dim];
         let (_b_shape, b_stride, b_suboffset) = other.dim_desc[dim];
         debug_assert_eq!(shape, _b_shape);
-        if dim + 1 == self.ndim() {
+        if pos + 1 == order.len() {
             if CONTIGUOUS {
                 if f(
                     a_index..a_index + (shape * self.itemsize) as isize,
@@ -362,9 +816,10 @@ impl BufferDescriptor {
         for _ in 0..shape {
             self._zip_eq::<F, CONTIGUOUS>(
                 other,
+                order,
                 a_index + a_suboffset,
                 b_index + b_suboffset,
-                dim + 1,
+                pos + 1,
                 f,
             );
             a_index += a_stride;
@@ -372,16 +827,9 @@ impl BufferDescriptor {
         }
     }
 
-    fn is_last_dim_contiguous(&self) -> bool {
-        let (_, stride, suboffset) = self.dim_desc[self.ndim() - 1];
-        suboffset == 0 && stride == self.itemsize as isize
-    }
-
     pub fn is_zero_in_shape(&self) -> bool {
         self.dim_desc.iter().any(|(shape, _, _)| *shape == 0)
     }
-
-    // TODO: support column-major order
 }
 
 pub trait BufferResizeGuard {
@@ -400,6 +848,11 @@ pub trait BufferResizeGuard {
 #[derive(Debug, PyPayload)]
 pub struct VecBuffer {
     data: PyMutex<Vec<u8>>,
+    /// Count of live `PyBuffer`s exported from this `VecBuffer` (via the
+    /// `retain`/`release` entries of [`VEC_BUFFER_METHODS`]). Resizing while this is
+    /// nonzero would move or invalidate memory another `PyBuffer` still borrows from,
+    /// so [`BufferResizeGuard::try_resizable_opt`] refuses while any export is live.
+    exports: AtomicUsize,
 }
 
 #[pyclass(flags(BASETYPE), with(Unconstructible))]
@@ -413,6 +866,7 @@ impl From<Vec<u8>> for VecBuffer {
     fn from(data: Vec<u8>) -> Self {
         Self {
             data: PyMutex::new(data),
+            exports: AtomicUsize::new(0),
         }
     }
 }
@@ -434,6 +888,41 @@ impl PyRef<VecBuffer> {
     }
 }
 
+/// Proof, obtained via [`BufferResizeGuard::try_resizable`], that no `PyBuffer` export
+/// of a `VecBuffer` is currently live, so growing or shrinking it can't invalidate
+/// memory another `PyBuffer` is borrowing from.
+pub struct VecBufferResizeGuard<'a> {
+    data: PyMutexGuard<'a, Vec<u8>>,
+}
+
+impl VecBufferResizeGuard<'_> {
+    pub fn resize(&mut self, new_len: usize, value: u8) {
+        self.data.resize(new_len, value);
+    }
+
+    pub fn extend_from_slice(&mut self, other: &[u8]) {
+        self.data.extend_from_slice(other);
+    }
+
+    pub fn reserve(&mut self, additional: usize) {
+        self.data.reserve(additional);
+    }
+}
+
+impl BufferResizeGuard for PyRef<VecBuffer> {
+    type Resizable<'a>
+        = VecBufferResizeGuard<'a>
+    where
+        Self: 'a;
+
+    fn try_resizable_opt(&self) -> Option<Self::Resizable<'_>> {
+        (self.exports.load(Ordering::SeqCst) == 0)
+            .then(|| VecBufferResizeGuard {
+                data: self.data.lock(),
+            })
+    }
+}
+
 static VEC_BUFFER_METHODS: BufferMethods = BufferMethods {
     obj_bytes: |buffer| {
         PyMutexGuard::map_immutable(buffer.obj_as::<VecBuffer>().data.lock(), |x| x.as_slice())
@@ -445,6 +934,169 @@ static VEC_BUFFER_METHODS: BufferMethods = BufferMethods {
         })
         .into()
     },
-    release: |_| {},
-    retain: |_| {},
+    release: |buffer| {
+        buffer.obj_as::<VecBuffer>().exports.fetch_sub(1, Ordering::SeqCst);
+    },
+    retain: |buffer| {
+        buffer.obj_as::<VecBuffer>().exports.fetch_add(1, Ordering::SeqCst);
+    },
 };
+
+/// Parsing for `struct`-module-style format strings, the mini-language PEP 3118 uses
+/// for [`BufferDescriptor::format`]: an optional byte-order prefix (`@<>=!`) followed by
+/// one or more type codes, each optionally preceded by a repeat count (e.g. `"<3h"` is
+/// three little-endian `short`s). This only computes the sizing CPython's buffer
+/// protocol needs (`itemsize`, `alignment`) — it doesn't (de)serialize values the way
+/// the `struct` module itself does.
+pub mod format {
+    /// How a format string's byte-order prefix, if any, was spelled. `Native` (`@` or no
+    /// prefix) is the only variant for which [`parse`] tracks an `alignment` above 1;
+    /// [`parse`] inserts no padding between codes in any mode, so `itemsize` is the
+    /// plain sum of the code sizes.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum ByteOrder {
+        Native,
+        LittleEndian,
+        BigEndian,
+        /// `=`: native byte order, but standard (non-native) sizes and no alignment.
+        Standard,
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct ParsedFormat {
+        pub itemsize: usize,
+        pub alignment: usize,
+        pub byteorder: ByteOrder,
+        pub is_native: bool,
+    }
+
+    /// Itemsize and (native-mode) alignment for one format code, per the `struct`
+    /// module's "format characters" table. `n`/`N` (`ssize_t`/`size_t`) are sized as
+    /// 8 bytes, matching every 64-bit target RustPython currently supports.
+    fn code_size_align(code: char) -> Option<(usize, usize)> {
+        Some(match code {
+            'b' | 'B' | 'c' | '?' | 's' | 'x' => (1, 1),
+            'h' | 'H' => (2, 2),
+            'i' | 'I' | 'l' | 'L' | 'f' => (4, 4),
+            'q' | 'Q' | 'd' | 'n' | 'N' => (8, 8),
+            _ => return None,
+        })
+    }
+
+    /// Parse a struct-style format string into its itemsize and alignment. Returns
+    /// `Err` with a human-readable message (suitable for wrapping in a `ValueError`) on
+    /// an empty format, an unrecognized code, or a repeat count with no code after it.
+    pub fn parse(format: &str) -> Result<ParsedFormat, String> {
+        let mut chars = format.chars().peekable();
+        let (byteorder, is_native) = match chars.peek() {
+            Some('@') => {
+                chars.next();
+                (ByteOrder::Native, true)
+            }
+            Some('=') => {
+                chars.next();
+                (ByteOrder::Standard, false)
+            }
+            Some('<') => {
+                chars.next();
+                (ByteOrder::LittleEndian, false)
+            }
+            Some('>' | '!') => {
+                chars.next();
+                (ByteOrder::BigEndian, false)
+            }
+            _ => (ByteOrder::Native, true),
+        };
+
+        let mut itemsize = 0usize;
+        let mut alignment = 1usize;
+        let mut saw_code = false;
+
+        while let Some(&c) = chars.peek() {
+            let count = if c.is_ascii_digit() {
+                let mut count = 0usize;
+                while let Some(d) = chars.peek().and_then(|c| c.to_digit(10)) {
+                    count = count * 10 + d as usize;
+                    chars.next();
+                }
+                count
+            } else {
+                1
+            };
+            let code = chars
+                .next()
+                .ok_or_else(|| "repeat count not followed by a format code".to_owned())?;
+            let (size, align) =
+                code_size_align(code).ok_or_else(|| format!("bad char in struct format: {code}"))?;
+            itemsize += size * count;
+            if is_native {
+                alignment = alignment.max(align);
+            }
+            saw_code = true;
+        }
+
+        if !saw_code {
+            return Err("empty format string".to_owned());
+        }
+
+        Ok(ParsedFormat {
+            itemsize,
+            alignment,
+            byteorder,
+            is_native,
+        })
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn single_code_with_no_prefix_is_native() {
+            let p = parse("i").unwrap();
+            assert_eq!(p.itemsize, 4);
+            assert_eq!(p.byteorder, ByteOrder::Native);
+            assert!(p.is_native);
+        }
+
+        #[test]
+        fn repeat_count_multiplies_itemsize() {
+            let p = parse("<3h").unwrap();
+            assert_eq!(p.itemsize, 6);
+            assert_eq!(p.byteorder, ByteOrder::LittleEndian);
+            assert!(!p.is_native);
+        }
+
+        #[test]
+        fn multiple_codes_sum_their_sizes() {
+            let p = parse("bi").unwrap();
+            assert_eq!(p.itemsize, 1 + 4);
+        }
+
+        /// Native mode tracks the widest code's alignment; standard/explicit-endian
+        /// modes never align (`struct`'s rule, not just this implementation's).
+        #[test]
+        fn native_mode_tracks_max_alignment_but_standard_mode_does_not() {
+            let native = parse("@bq").unwrap();
+            assert_eq!(native.alignment, 8);
+            let standard = parse("=bq").unwrap();
+            assert_eq!(standard.alignment, 1);
+        }
+
+        #[test]
+        fn empty_format_is_an_error() {
+            assert!(parse("").is_err());
+            assert!(parse("<").is_err());
+        }
+
+        #[test]
+        fn unrecognized_code_is_an_error() {
+            assert!(parse("z").is_err());
+        }
+
+        #[test]
+        fn dangling_repeat_count_is_an_error() {
+            assert!(parse("3").is_err());
+        }
+    }
+}
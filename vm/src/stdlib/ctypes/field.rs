@@ -1,4 +1,5 @@
 use crate::builtins::PyType;
+use crate::function::OptionalArg;
 
 #[pyclass(name = "PyCFieldType", base = "PyType", module = "_ctypes")]
 #[derive(PyPayload)]
@@ -21,7 +22,7 @@ pub struct PyCField {
     index: usize,
     proto: PyTypeRef,
     anonymous: bool,
-    bitfield_size: bool,
+    bitfield_size: usize,
     bit_offset: u8,
     name: String,
 }
@@ -52,17 +53,433 @@ impl PyCField {
     }
 
     #[pygetset]
-    fn bit_size(&self) -> u8 {
+    fn bit_size(&self) -> usize {
         self.bitfield_size
     }
 
     #[pygetset]
     fn is_bitfield(&self) -> bool {
-        self.bitfield_size
+        self.bitfield_size != 0
     }
 
     #[pygetset]
     fn is_anonymous(&self) -> bool {
         self.anonymous
     }
+
+    /// Read this field out of `instance`'s backing buffer.
+    ///
+    /// For a plain field this is just the bytes at `byte_offset`; for a bitfield it
+    /// loads the whole storage unit, shifts the requested bits down to position 0, and
+    /// masks them off, sign-extending if `proto` is a signed integer type.
+    #[pymethod(magic)]
+    fn get(
+        &self,
+        instance: PyObjectRef,
+        _owner: OptionalArg<PyObjectRef>,
+        vm: &VirtualMachine,
+    ) -> PyResult {
+        let data = instance
+            .downcast::<PyCData>()
+            .map_err(|_| vm.new_type_error("not a ctypes instance".to_owned()))?;
+        let bytes = data.buffer_bytes();
+        let unit = &bytes[self.byte_offset..self.byte_offset + self.byte_size];
+
+        if self.bitfield_size == 0 {
+            return Ok(raw_to_pyobject(unit, &self.proto, vm));
+        }
+
+        let mut value = bits::get_range(unit, self.bit_offset as usize, self.bitfield_size);
+        let signed = is_signed_proto(&self.proto);
+        if signed {
+            let mask = (1u64 << self.bitfield_size) - 1;
+            if value & (1 << (self.bitfield_size - 1)) != 0 {
+                value |= !mask;
+            }
+            Ok(vm.ctx.new_int(value as i64).into())
+        } else {
+            Ok(vm.ctx.new_int(value).into())
+        }
+    }
+
+    /// Write `value` into this field's bits within `instance`'s backing buffer, leaving
+    /// the surrounding bits of the storage unit (neighboring bitfields) untouched.
+    #[pymethod(magic)]
+    fn set(&self, instance: PyObjectRef, value: PyObjectRef, vm: &VirtualMachine) -> PyResult<()> {
+        let data = instance
+            .downcast::<PyCData>()
+            .map_err(|_| vm.new_type_error("not a ctypes instance".to_owned()))?;
+        let mut bytes = data.buffer_bytes_mut();
+        let unit = &mut bytes[self.byte_offset..self.byte_offset + self.byte_size];
+
+        if self.bitfield_size == 0 {
+            return pyobject_to_raw(unit, &self.proto, value, vm);
+        }
+
+        let new_value = value.try_int(vm)?.as_bigint().clone();
+        let new_value: i64 = new_value
+            .try_into()
+            .map_err(|_| vm.new_overflow_error("value out of range for bitfield".to_owned()))?;
+
+        bits::set_range(
+            unit,
+            self.bit_offset as usize,
+            self.bitfield_size,
+            new_value as u64,
+        );
+        Ok(())
+    }
+}
+
+/// Endian-correct addressing of individual bits within a byte buffer.
+///
+/// Bit numbering inside a storage byte differs between little- and big-endian targets:
+/// little-endian numbers bits LSB-first within each byte, big-endian MSB-first. Every
+/// bitfield read/write goes through here so a `PyCField` produces the same in-memory
+/// layout a C compiler would on the target architecture.
+mod bits {
+    #[cfg(target_endian = "little")]
+    fn locate(index: usize) -> (usize, u32) {
+        (index / 8, (index % 8) as u32)
+    }
+
+    #[cfg(target_endian = "big")]
+    fn locate(index: usize) -> (usize, u32) {
+        (index / 8, (7 - index % 8) as u32)
+    }
+
+    fn get_bit(storage: &[u8], index: usize) -> bool {
+        let (byte, bit) = locate(index);
+        storage[byte] & (1 << bit) != 0
+    }
+
+    fn set_bit(storage: &mut [u8], index: usize, value: bool) {
+        let (byte, bit) = locate(index);
+        if value {
+            storage[byte] |= 1 << bit;
+        } else {
+            storage[byte] &= !(1 << bit);
+        }
+    }
+
+    /// Read `width` logical bits starting at `start`, LSB of the result first.
+    pub(super) fn get_range(storage: &[u8], start: usize, width: usize) -> u64 {
+        let mut value: u64 = 0;
+        for i in 0..width {
+            if get_bit(storage, start + i) {
+                value |= 1 << i;
+            }
+        }
+        value
+    }
+
+    /// Write the low `width` bits of `value` starting at `start`, leaving every other
+    /// bit of `storage` untouched.
+    pub(super) fn set_range(storage: &mut [u8], start: usize, width: usize, value: u64) {
+        for i in 0..width {
+            set_bit(storage, start + i, value & (1 << i) != 0);
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn round_trip_within_a_single_byte() {
+            let mut storage = [0u8; 1];
+            set_range(&mut storage, 2, 4, 0b1011);
+            assert_eq!(get_range(&storage, 2, 4), 0b1011);
+        }
+
+        /// A bitfield whose width straddles a byte boundary must read back exactly what
+        /// was written, regardless of how many surrounding bits are set.
+        #[test]
+        fn round_trip_crosses_a_byte_boundary() {
+            let mut storage = [0xffu8; 2];
+            set_range(&mut storage, 5, 7, 0b0101101);
+            assert_eq!(get_range(&storage, 5, 7), 0b0101101);
+            // Bits outside the range are untouched.
+            assert!(get_bit(&storage, 0));
+            assert!(get_bit(&storage, 15));
+        }
+
+        /// `get_range` itself is unsigned; sign-extension (as `PyCField::get` applies
+        /// for signed proto types) is a separate masking step layered on top.
+        #[test]
+        fn negative_value_sign_extends_after_masking() {
+            let mut storage = [0u8; 1];
+            let width = 5;
+            set_range(&mut storage, 0, width, (-3i64) as u64);
+            let mut value = get_range(&storage, 0, width);
+            let mask = (1u64 << width) - 1;
+            if value & (1 << (width - 1)) != 0 {
+                value |= !mask;
+            }
+            assert_eq!(value as i64, -3);
+        }
+
+        #[cfg(target_endian = "little")]
+        #[test]
+        fn little_endian_numbers_bits_lsb_first() {
+            let mut storage = [0u8; 1];
+            set_bit(&mut storage, 0, true);
+            assert_eq!(storage[0], 0b0000_0001);
+        }
+    }
+}
+
+/// Whether `proto` is one of the signed integer types, whose bitfields are
+/// sign-extended on read.
+fn is_signed_proto(proto: &PyTypeRef) -> bool {
+    matches!(
+        proto.name(),
+        "c_byte" | "c_short" | "c_int" | "c_long" | "c_longlong"
+    )
+}
+
+/// Decode a non-bitfield field's raw bytes using its base type's own buffer conversion.
+fn raw_to_pyobject(bytes: &[u8], proto: &PyTypeRef, vm: &VirtualMachine) -> PyObjectRef {
+    super::simple::from_buffer(proto, bytes, vm)
+}
+
+/// Encode `value` into a non-bitfield field's raw bytes using its base type's own buffer
+/// conversion.
+fn pyobject_to_raw(
+    bytes: &mut [u8],
+    proto: &PyTypeRef,
+    value: PyObjectRef,
+    vm: &VirtualMachine,
+) -> PyResult<()> {
+    super::simple::write_buffer(proto, bytes, value, vm)
+}
+
+/// CPython's `_ctypes` bitfield layout engine (`PyCStructUnionType_update_stgdict`).
+///
+/// Structs pack consecutive bitfields into shared storage units the same way a C
+/// compiler does; which fields share a unit, and where in it, depends on whether the
+/// platform follows the MSVC or the GCC/SysV convention.
+pub mod layout {
+    use super::PyCField;
+    use crate::{PyResult, VirtualMachine};
+
+    /// Which C ABI's bitfield packing rules to follow.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum PackingMode {
+        Msvc,
+        Gcc,
+    }
+
+    /// A field awaiting layout: its name, the byte size of its base type (`dict_size`),
+    /// its natural alignment, the type's Python name (for error messages), its simple
+    /// type code if it has one (`i/I/h/H/b/B/l/L/q/Q/...`), and, for bitfields, the
+    /// requested bit width.
+    pub struct FieldRequest<'a> {
+        pub name: &'a str,
+        pub type_name: &'a str,
+        pub simple_code: Option<char>,
+        pub dict_size: usize,
+        pub dict_align: usize,
+        pub bitsize: Option<usize>,
+    }
+
+    /// The simple type codes a bitfield may be based on, per CPython's `_ctypes`.
+    const BITFIELD_CODES: &[char] =
+        &['i', 'I', 'h', 'H', 'b', 'B', 'l', 'L', 'q', 'Q'];
+
+    fn check_bitfield_type(field: &FieldRequest<'_>, vm: &VirtualMachine) -> PyResult<()> {
+        if field.bitsize.is_none() {
+            return Ok(());
+        }
+        match field.simple_code {
+            Some(code) if BITFIELD_CODES.contains(&code) => Ok(()),
+            _ => Err(vm.new_type_error(format!(
+                "bit fields not allowed for type {}",
+                field.type_name
+            ))),
+        }
+    }
+
+    /// Running state while walking a `Structure`/`Union`'s fields: the bit width of the
+    /// currently open bitfield storage unit (0 if none), the next free bit within it, and
+    /// the byte offset the unit itself starts at (not derivable from `offset`, since a GCC
+    /// unit can widen past the base size of the field that opened it).
+    #[derive(Debug, Clone, Copy, Default)]
+    struct Open {
+        field_size: usize,
+        bit_ofs: usize,
+        unit_start: usize,
+    }
+
+    fn align_up(offset: usize, align: usize) -> usize {
+        if align == 0 {
+            offset
+        } else {
+            (offset + align - 1) / align * align
+        }
+    }
+
+    /// Lay out `fields` in declaration order, returning the `(byte_offset, bit_offset,
+    /// bitfield_size, byte_size)` CPython would assign to each one. `union` lays every
+    /// field at offset 0 instead of advancing `offset`.
+    pub fn layout_fields(
+        fields: &[FieldRequest<'_>],
+        mode: PackingMode,
+        union: bool,
+        vm: &VirtualMachine,
+    ) -> PyResult<Vec<(usize, u8, usize, usize)>> {
+        let mut out = Vec::with_capacity(fields.len());
+        let mut offset = 0usize;
+        let mut open = Open::default();
+
+        for field in fields {
+            check_bitfield_type(field, vm)?;
+
+            let Some(bitsize) = field.bitsize else {
+                // A non-bitfield member always closes any currently open storage unit.
+                open = Open::default();
+                let start = if union {
+                    0
+                } else {
+                    align_up(offset, field.dict_align)
+                };
+                out.push((start, 0, 0, field.dict_size));
+                if !union {
+                    offset = start + field.dict_size;
+                }
+                continue;
+            };
+
+            let dict_bits = field.dict_size * 8;
+            let continues = open.field_size != 0
+                && match mode {
+                    PackingMode::Msvc => {
+                        dict_bits == open.field_size && open.bit_ofs + bitsize <= open.field_size
+                    }
+                    PackingMode::Gcc => {
+                        (dict_bits <= open.field_size && open.bit_ofs + bitsize <= open.field_size)
+                            || (dict_bits >= open.field_size
+                                && open.bit_ofs + bitsize <= dict_bits)
+                    }
+                };
+
+            if continues {
+                if mode == PackingMode::Gcc && dict_bits > open.field_size {
+                    open.field_size = dict_bits;
+                }
+                let unit_offset = if union { 0 } else { open.unit_start };
+                let bit_offset = open.bit_ofs as u8;
+                open.bit_ofs += bitsize;
+                out.push((unit_offset, bit_offset, bitsize, field.dict_size));
+            } else {
+                // Close the previous unit (if any) and open a fresh, aligned one.
+                let start = if union {
+                    0
+                } else {
+                    align_up(offset, field.dict_align)
+                };
+                open.field_size = dict_bits;
+                open.bit_ofs = bitsize;
+                open.unit_start = start;
+                out.push((start, 0, bitsize, field.dict_size));
+                if !union {
+                    offset = start + field.dict_size;
+                }
+            }
+        }
+
+        Ok(out)
+    }
+
+    /// Apply a computed layout entry to a freshly constructed [`PyCField`].
+    pub fn apply(field: &mut PyCField, layout: (usize, u8, usize, usize)) {
+        let (byte_offset, bit_offset, bitfield_size, byte_size) = layout;
+        field.byte_offset = byte_offset;
+        field.bit_offset = bit_offset;
+        field.bitfield_size = bitfield_size;
+        field.byte_size = byte_size;
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use crate::Interpreter;
+
+        fn req<'a>(name: &'a str, dict_size: usize, bitsize: Option<usize>) -> FieldRequest<'a> {
+            FieldRequest {
+                name,
+                type_name: "c_int",
+                simple_code: Some('i'),
+                dict_size,
+                dict_align: dict_size,
+                bitsize,
+            }
+        }
+
+        /// Two `int` bitfields that together fit in one 4-byte storage unit pack into
+        /// the same unit under both ABIs.
+        #[test]
+        fn adjacent_bitfields_share_a_storage_unit() {
+            Interpreter::without_stdlib(Default::default()).enter(|vm| {
+                let fields = vec![req("a", 4, Some(3)), req("b", 4, Some(5))];
+                let msvc = layout_fields(&fields, PackingMode::Msvc, false, vm).unwrap();
+                let gcc = layout_fields(&fields, PackingMode::Gcc, false, vm).unwrap();
+                assert_eq!(msvc, vec![(0, 0, 3, 4), (0, 3, 5, 4)]);
+                assert_eq!(gcc, vec![(0, 0, 3, 4), (0, 3, 5, 4)]);
+            });
+        }
+
+        /// MSVC never spans a bitfield across two different-width base types into the
+        /// same unit; GCC/SysV does when the new field still fits the open unit's bits.
+        #[test]
+        fn msvc_and_gcc_diverge_on_mixed_width_bitfields() {
+            Interpreter::without_stdlib(Default::default()).enter(|vm| {
+                let short_then_int = vec![
+                    FieldRequest {
+                        dict_align: 2,
+                        ..req("a", 2, Some(4))
+                    },
+                    req("b", 4, Some(4)),
+                ];
+                let msvc = layout_fields(&short_then_int, PackingMode::Msvc, false, vm).unwrap();
+                let gcc = layout_fields(&short_then_int, PackingMode::Gcc, false, vm).unwrap();
+                // MSVC: differing declared width closes the unit, so `b` starts a new one
+                // aligned to its own (4-byte) type, at offset 4 - not packed at offset 2.
+                assert_eq!(msvc, vec![(0, 0, 4, 2), (4, 0, 4, 4)]);
+                // GCC: `b`'s bits still fit after widening the open unit to 32 bits.
+                assert_eq!(gcc, vec![(0, 0, 4, 2), (0, 4, 4, 4)]);
+            });
+        }
+
+        /// A non-bitfield member always closes whatever bitfield unit was open.
+        #[test]
+        fn plain_field_closes_open_bitfield_unit() {
+            Interpreter::without_stdlib(Default::default()).enter(|vm| {
+                let fields = vec![req("a", 4, Some(3)), req("b", 4, None)];
+                let out = layout_fields(&fields, PackingMode::Gcc, false, vm).unwrap();
+                assert_eq!(out, vec![(0, 0, 3, 4), (4, 0, 0, 4)]);
+            });
+        }
+
+        /// In a union every member (bitfield or not) starts at offset 0.
+        #[test]
+        fn union_members_all_start_at_zero() {
+            Interpreter::without_stdlib(Default::default()).enter(|vm| {
+                let fields = vec![req("a", 4, Some(3)), req("b", 4, None)];
+                let out = layout_fields(&fields, PackingMode::Gcc, true, vm).unwrap();
+                assert_eq!(out, vec![(0, 0, 3, 4), (0, 0, 0, 4)]);
+            });
+        }
+
+        #[test]
+        fn bitfield_on_unsupported_base_type_is_rejected() {
+            Interpreter::without_stdlib(Default::default()).enter(|vm| {
+                let fields = vec![FieldRequest {
+                    simple_code: None,
+                    ..req("a", 4, Some(3))
+                }];
+                assert!(layout_fields(&fields, PackingMode::Gcc, false, vm).is_err());
+            });
+        }
+    }
 }
@@ -3,23 +3,25 @@ pub(crate) use _typing::make_module;
 #[pymodule]
 pub(crate) mod _typing {
     use crate::{
-        Py, PyObjectRef, PyPayload, PyResult, VirtualMachine,
-        builtins::{PyGenericAlias, PyTupleRef, PyTypeRef, pystr::AsPyStr},
+        AsObject, Py, PyObjectRef, PyPayload, PyRef, PyResult, VirtualMachine, atomic_func,
+        builtins::{PyGenericAlias, PyList, PyTuple, PyTupleRef, PyTypeRef, pystr::AsPyStr},
         convert::ToPyResult,
         function::{FuncArgs, IntoFuncArgs},
-        types::{Constructor, Representable}
+        protocol::PyMappingMethods,
+        types::{AsMapping, Constructor, Representable},
     };
 
+    /// Call a function out of the pure-Python `typing` module by name — the bridge the
+    /// rest of this native `_typing` module leans on instead of reimplementing
+    /// `typing.py`'s generics machinery (`_GenericAlias` construction, etc.) in Rust.
     pub(crate) fn _call_typing_func_object<'a>(
-        _vm: &VirtualMachine,
-        _func_name: impl AsPyStr<'a>,
-        _args: impl IntoFuncArgs,
+        vm: &VirtualMachine,
+        func_name: impl AsPyStr<'a>,
+        args: impl IntoFuncArgs,
     ) -> PyResult {
-        todo!("does this work????");
-        // let module = vm.import("typing", 0)?;
-        // let module = vm.import("_pycodecs", None, 0)?;
-        // let func = module.get_attr(func_name, vm)?;
-        // func.call(args, vm)
+        let module = vm.import("typing", None, 0)?;
+        let func = module.get_attr(func_name, vm)?;
+        func.call(args, vm)
     }
 
     #[pyfunction]
@@ -37,11 +39,71 @@ pub(crate) mod _typing {
         evaluate_bound: PyObjectRef,
         constraints: parking_lot::Mutex<PyObjectRef>,
         evaluate_constraints: PyObjectRef,
+        default_value: Option<PyObjectRef>,
+        evaluate_default: Option<PyObjectRef>,
+        covariant: bool,
+        contravariant: bool,
+        infer_variance: bool,
+    }
+
+    #[derive(FromArgs, Debug)]
+    pub(crate) struct TypeVarConstructorArgs {
+        #[pyarg(positional)]
+        name: PyObjectRef,
+        #[pyarg(args)]
+        constraints: Vec<PyObjectRef>,
+        #[pyarg(named, default)]
+        bound: Option<PyObjectRef>,
+        // TODO: Default is actually _Py_NoDefaultStruct
+        #[pyarg(named, default = None)]
+        default: Option<PyObjectRef>,
+        #[pyarg(named, default = false)]
         covariant: bool,
+        #[pyarg(named, default = false)]
         contravariant: bool,
+        #[pyarg(named, default = false)]
         infer_variance: bool,
     }
 
+    impl Constructor for TypeVar {
+        type Args = TypeVarConstructorArgs;
+
+        fn py_new(_cls: PyTypeRef, args: Self::Args, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+            // Mirrors CPython's typevarobject.c `typevar_new` invariant checks.
+            if args.covariant && args.contravariant {
+                return Err(vm.new_value_error("Bivariant type variables are not supported.".to_string()));
+            }
+            if args.infer_variance && (args.covariant || args.contravariant) {
+                return Err(vm.new_value_error(
+                    "Variance cannot be specified with infer_variance.".to_string(),
+                ));
+            }
+            if args.bound.is_some() && !args.constraints.is_empty() {
+                return Err(vm.new_type_error(
+                    "Constraints cannot be combined with a bound.".to_string(),
+                ));
+            }
+            if args.constraints.len() == 1 {
+                return Err(vm.new_type_error("A single constraint is not allowed".to_string()));
+            }
+            let bound = args.bound.unwrap_or_else(|| vm.ctx.none());
+            let constraints = vm.new_tuple(args.constraints);
+            let typevar = TypeVar {
+                name: args.name,
+                bound: parking_lot::Mutex::new(bound),
+                evaluate_bound: vm.ctx.none(),
+                constraints: parking_lot::Mutex::new(constraints.into()),
+                evaluate_constraints: vm.ctx.none(),
+                default_value: args.default,
+                evaluate_default: None,
+                covariant: args.covariant,
+                contravariant: args.contravariant,
+                infer_variance: args.infer_variance,
+            };
+            typevar.to_pyresult(vm)
+        }
+    }
+
     impl Representable for TypeVar {
         #[inline]
         fn repr_str(zelf: &Py<Self>, vm: &VirtualMachine) -> PyResult<String> {
@@ -61,7 +123,7 @@ pub(crate) mod _typing {
         }
     }
 
-    #[pyclass(flags(BASETYPE), with(Representable))]
+    #[pyclass(flags(BASETYPE), with(Constructor, Representable))]
     impl TypeVar {
         pub(crate) fn _bound(&self, vm: &VirtualMachine) -> PyResult {
             let mut bound = self.bound.lock();
@@ -76,6 +138,29 @@ pub(crate) mod _typing {
             }
         }
 
+        pub(crate) fn _constraints(&self, vm: &VirtualMachine) -> PyResult {
+            let mut constraints = self.constraints.lock();
+            if !vm.is_none(&constraints) {
+                return Ok(constraints.clone());
+            }
+            if !vm.is_none(&self.evaluate_constraints) {
+                *constraints = self.evaluate_constraints.call((), vm)?;
+                Ok(constraints.clone())
+            } else {
+                Ok(vm.new_tuple(vec![]).into())
+            }
+        }
+
+        #[pygetset(magic)]
+        fn bound(&self, vm: &VirtualMachine) -> PyResult {
+            self._bound(vm)
+        }
+
+        #[pygetset(magic)]
+        fn constraints(&self, vm: &VirtualMachine) -> PyResult {
+            self._constraints(vm)
+        }
+
         #[pygetset(magic)]
         fn name(&self) -> PyObjectRef {
             self.name.clone()
@@ -96,10 +181,64 @@ pub(crate) mod _typing {
             self.infer_variance
         }
 
+        #[pygetset(magic)]
+        fn default(&self, vm: &VirtualMachine) -> PyResult {
+            if let Some(default_value) = self.default_value.clone() {
+                return Ok(default_value);
+            }
+            if let Some(evaluate_default) = self.evaluate_default.clone() {
+                return vm.call_method(evaluate_default.as_ref(), "__call__", ());
+            }
+            Ok(no_default_singleton(vm))
+        }
+
+        #[pymethod]
+        fn has_default(&self) -> bool {
+            self.default_value.is_some() || self.evaluate_default.is_some()
+        }
+
         #[pymethod(magic)]
         fn mro_entries(&self, vm: &VirtualMachine) -> PyResult {
             Err(vm.new_type_error("Cannot subclass an instance of TypeVar".to_string()))
         }
+
+        /// What a subscripted generic substitutes for this `TypeVar`: `arg` itself in
+        /// the ordinary case (including when `arg` is another `TypeVar`, so nested
+        /// generics chain through unchanged), except a `ParamSpec`/`TypeVarTuple`
+        /// value, which can never fill a plain `TypeVar` slot.
+        #[pymethod(magic)]
+        fn typing_subst(&self, arg: PyObjectRef, vm: &VirtualMachine) -> PyResult {
+            if arg.payload_is::<ParamSpec>() || arg.payload_is::<TypeVarTuple>() {
+                return Err(vm.new_type_error(format!(
+                    "{} is not valid as type argument",
+                    arg.class().name()
+                )));
+            }
+            Ok(arg)
+        }
+
+        #[pymethod(magic)]
+        fn or(zelf: PyRef<Self>, other: PyObjectRef, vm: &VirtualMachine) -> PyResult {
+            build_union(zelf.into(), other, vm)
+        }
+
+        #[pymethod(magic)]
+        fn ror(zelf: PyRef<Self>, other: PyObjectRef, vm: &VirtualMachine) -> PyResult {
+            build_union(other, zelf.into(), vm)
+        }
+    }
+
+    /// `T | int`: build a `Union` the same way CPython's pure-Python `typing.py`
+    /// fallback does it (`TypeVar.__or__`/`__ror__` both just return
+    /// `Union[self, other]`/`Union[other, self]`), rather than reimplementing
+    /// `types.UnionType` construction here. Shared by every type-parameter object
+    /// that needs `__or__`/`__ror__`: `TypeVar`, `ParamSpec`, `TypeVarTuple`, and
+    /// `TypeAliasType`.
+    fn build_union(left: PyObjectRef, right: PyObjectRef, vm: &VirtualMachine) -> PyResult {
+        let typing = vm.import("typing", None, 0)?;
+        let union = typing.get_attr("Union", vm)?;
+        let args = vm.new_tuple(vec![left, right]);
+        vm.call_method(&union, "__getitem__", (args,))
     }
 
     pub(crate) fn make_typevar(
@@ -114,12 +253,33 @@ pub(crate) mod _typing {
             evaluate_bound,
             constraints: parking_lot::Mutex::new(vm.ctx.none()),
             evaluate_constraints,
+            default_value: None,
+            evaluate_default: None,
             covariant: false,
             contravariant: false,
             infer_variance: true,
         }
     }
 
+    /// The shared `typing.NoDefault` sentinel instance, constructed once per
+    /// interpreter and reused for every `TypeVar`/`ParamSpec`/`TypeVarTuple` that has
+    /// no default — mirroring CPython's single process-wide `_Py_NoDefaultStruct`, so
+    /// `T.__default__ is typing.NoDefault` holds regardless of which type-parameter
+    /// object asked. Held on `vm.state` rather than a process-wide `static`: a
+    /// `static` would require `PyObjectRef: Send + Sync` (only true in the
+    /// `threading` build) and would hand every subinterpreter spawned via
+    /// `VirtualMachine::new_subinterpreter` the *first* interpreter's sentinel
+    /// forever, breaking `is`-identity for any subsequent interpreter.
+    fn no_default_singleton(vm: &VirtualMachine) -> PyObjectRef {
+        let mut slot = vm.state.no_default_sentinel.lock();
+        slot.get_or_insert_with(|| {
+            NoDefault { name: vm.ctx.none() }
+                .to_pyresult(vm)
+                .expect("constructing the NoDefault sentinel cannot fail")
+        })
+        .clone()
+    }
+
     #[pyattr]
     #[pyclass(name = "ParamSpec")]
     #[derive(Debug, PyPayload)]
@@ -201,18 +361,32 @@ pub(crate) mod _typing {
             self.infer_variance
         }
 
+        /// `P.args`: a `ParamSpecArgs` bound back to this `ParamSpec`, for annotating
+        /// `*args` in a `Callable[P, ...]` signature.
+        #[pygetset]
+        fn args(zelf: PyRef<Self>) -> ParamSpecArgs {
+            ParamSpecArgs {
+                origin: zelf.into(),
+            }
+        }
+
+        /// `P.kwargs`: the `**kwargs`-side counterpart of [`ParamSpec::args`].
+        #[pygetset]
+        fn kwargs(zelf: PyRef<Self>) -> ParamSpecKwargs {
+            ParamSpecKwargs {
+                origin: zelf.into(),
+            }
+        }
+
         #[pygetset(magic)]
         fn default(&self, vm: &VirtualMachine) -> PyResult {
             if let Some(default_value) = self.default_value.clone() {
                 return Ok(default_value);
             }
-            // handle evaluate_default
             if let Some(evaluate_default) = self.evaluate_default.clone() {
-                let default_value = vm.call_method(evaluate_default.as_ref(), "__call__", ())?;
-                return Ok(default_value);
+                return vm.call_method(evaluate_default.as_ref(), "__call__", ());
             }
-            // TODO: this isn't up to spec
-            Ok(vm.ctx.none())
+            Ok(no_default_singleton(vm))
         }
 
         #[pygetset]
@@ -230,15 +404,78 @@ pub(crate) mod _typing {
         }
 
         #[pymethod]
-        fn has_default(&self) -> PyResult<bool> {
-            // TODO: fix
-            Ok(self.evaluate_default.is_some() || self.default_value.is_some())
+        fn has_default(&self) -> bool {
+            self.default_value.is_some() || self.evaluate_default.is_some()
         }
 
         #[pymethod(magic)]
         fn mro_entries(&self, vm: &VirtualMachine) -> PyResult {
             Err(vm.new_type_error("Cannot subclass an instance of ParamSpec".to_string()))
         }
+
+        /// A `ParamSpec` substitution must describe a whole parameter list: a
+        /// list/tuple of types, `...`, another `ParamSpec`, or `P.args`/`P.kwargs`
+        /// (when re-substituting one already split apart) — never a bare type, which
+        /// is what a plain `TypeVar` would accept instead.
+        #[pymethod(magic)]
+        fn typing_subst(&self, arg: PyObjectRef, vm: &VirtualMachine) -> PyResult {
+            let valid = arg.payload_is::<ParamSpec>()
+                || arg.payload_is::<ParamSpecArgs>()
+                || arg.payload_is::<ParamSpecKwargs>()
+                || arg.payload_is::<PyList>()
+                || arg.payload_is::<PyTuple>()
+                || arg.class().name().as_str() == "ellipsis";
+            if !valid {
+                return Err(vm.new_type_error(format!(
+                    "Expected a list of types, an ellipsis, ParamSpec, or Concatenate. Got {}.",
+                    arg.class().name()
+                )));
+            }
+            Ok(arg)
+        }
+
+        /// When more arguments are given than there are parameters, a `ParamSpec` in
+        /// the last slot of a generic absorbs every trailing argument beyond the other
+        /// parameters' count into a single list, the same way `Callable[[int, str], R]`
+        /// substitutes `P = [int, str]` rather than two separate positional arguments.
+        /// Otherwise `args` is returned unchanged.
+        #[pymethod(magic)]
+        fn typing_prepare_subst(
+            zelf: PyRef<Self>,
+            alias: PyObjectRef,
+            args: PyObjectRef,
+            vm: &VirtualMachine,
+        ) -> PyResult {
+            let params = alias.get_attr("__parameters__", vm)?;
+            let params = params.try_into_value::<Vec<PyObjectRef>>(vm)?;
+            let args_vec = args.try_into_value::<Vec<PyObjectRef>>(vm)?;
+
+            let our_index = params
+                .iter()
+                .position(|p| p.is(zelf.as_object()))
+                .ok_or_else(|| {
+                    vm.new_type_error("ParamSpec not found in type parameters".to_string())
+                })?;
+
+            if our_index == params.len() - 1 && args_vec.len() > params.len() {
+                let mut result = args_vec[..our_index].to_vec();
+                let tail = args_vec[our_index..].to_vec();
+                result.push(vm.ctx.new_list(tail).into());
+                return Ok(vm.new_tuple(result).into());
+            }
+
+            Ok(args)
+        }
+
+        #[pymethod(magic)]
+        fn or(zelf: PyRef<Self>, other: PyObjectRef, vm: &VirtualMachine) -> PyResult {
+            build_union(zelf.into(), other, vm)
+        }
+
+        #[pymethod(magic)]
+        fn ror(zelf: PyRef<Self>, other: PyObjectRef, vm: &VirtualMachine) -> PyResult {
+            build_union(other, zelf.into(), vm)
+        }
     }
 
     pub(crate) fn make_paramspec(name: PyObjectRef) -> ParamSpec {
@@ -293,29 +530,198 @@ pub(crate) mod _typing {
     #[allow(dead_code)]
     pub(crate) struct TypeVarTuple {
         name: PyObjectRef,
+        default_value: Option<PyObjectRef>,
+        evaluate_default: Option<PyObjectRef>,
+    }
+
+    #[derive(FromArgs, Debug)]
+    pub(crate) struct TypeVarTupleConstructorArgs {
+        #[pyarg(positional)]
+        name: PyObjectRef,
+        // TODO: Default is actually _Py_NoDefaultStruct
+        #[pyarg(named, default = None)]
+        default: Option<PyObjectRef>,
+    }
+
+    impl Constructor for TypeVarTuple {
+        type Args = TypeVarTupleConstructorArgs;
+
+        fn py_new(_cls: PyTypeRef, args: Self::Args, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+            let typevartuple = TypeVarTuple {
+                name: args.name,
+                default_value: args.default,
+                evaluate_default: None,
+            };
+            typevartuple.to_pyresult(vm)
+        }
+    }
+
+    #[pyclass(flags(BASETYPE), with(Constructor))]
+    impl TypeVarTuple {
+        #[pygetset(magic)]
+        fn name(&self) -> PyObjectRef {
+            self.name.clone()
+        }
+
+        /// Per CPython's `typevartupleobject.c`: an unset default is semantically an
+        /// empty-tuple default (an unspecified `*Ts` absorbs zero types), but
+        /// `__default__` itself still reports the shared `NoDefault` sentinel when
+        /// nothing was set — callers that need the empty-tuple behavior (substitution)
+        /// go through `typing_prepare_subst`, not through this getset.
+        #[pygetset(magic)]
+        fn default(&self, vm: &VirtualMachine) -> PyResult {
+            if let Some(default_value) = self.default_value.clone() {
+                return Ok(default_value);
+            }
+            if let Some(evaluate_default) = self.evaluate_default.clone() {
+                return vm.call_method(evaluate_default.as_ref(), "__call__", ());
+            }
+            Ok(no_default_singleton(vm))
+        }
+
+        #[pymethod]
+        fn has_default(&self) -> bool {
+            self.default_value.is_some() || self.evaluate_default.is_some()
+        }
+
+        /// How many of `args` the star-param consumes: everything beyond what the
+        /// generic's other, non-variadic type parameters need, spliced into one
+        /// sub-tuple at this `TypeVarTuple`'s position. A generic can only have one
+        /// `TypeVarTuple`, so more than one in `alias.__parameters__` is a `TypeError`.
+        #[pymethod(magic)]
+        fn typing_prepare_subst(
+            zelf: PyRef<Self>,
+            alias: PyObjectRef,
+            args: PyObjectRef,
+            vm: &VirtualMachine,
+        ) -> PyResult {
+            let params = alias.get_attr("__parameters__", vm)?;
+            let params = params.try_into_value::<Vec<PyObjectRef>>(vm)?;
+            let args_vec = args.try_into_value::<Vec<PyObjectRef>>(vm)?;
+
+            let mut our_index = None;
+            let mut other_typevartuples = 0usize;
+            for (i, p) in params.iter().enumerate() {
+                if p.is(zelf.as_object()) {
+                    our_index = Some(i);
+                } else if p.payload_is::<TypeVarTuple>() {
+                    other_typevartuples += 1;
+                }
+            }
+            let our_index = our_index.ok_or_else(|| {
+                vm.new_type_error("TypeVarTuple not found in type parameters".to_string())
+            })?;
+            if other_typevartuples > 0 {
+                return Err(vm.new_type_error(
+                    "More than one TypeVarTuple parameter in a generic is not supported"
+                        .to_string(),
+                ));
+            }
+
+            let other_params = params.len() - 1;
+            if args_vec.len() < other_params {
+                return Err(vm.new_type_error(format!(
+                    "Too few arguments; actual {}, expected at least {}",
+                    args_vec.len(),
+                    other_params
+                )));
+            }
+
+            let prefix_len = our_index;
+            let suffix_len = other_params - prefix_len;
+            let star_len = args_vec.len() - other_params;
+
+            let mut result = Vec::with_capacity(other_params + 1);
+            result.extend_from_slice(&args_vec[..prefix_len]);
+            let star_args = vm.new_tuple(args_vec[prefix_len..prefix_len + star_len].to_vec());
+            result.push(star_args.into());
+            result.extend_from_slice(
+                &args_vec[prefix_len + star_len..prefix_len + star_len + suffix_len],
+            );
+
+            Ok(vm.new_tuple(result).into())
+        }
+
+        #[pymethod(magic)]
+        fn or(zelf: PyRef<Self>, other: PyObjectRef, vm: &VirtualMachine) -> PyResult {
+            build_union(zelf.into(), other, vm)
+        }
+
+        #[pymethod(magic)]
+        fn ror(zelf: PyRef<Self>, other: PyObjectRef, vm: &VirtualMachine) -> PyResult {
+            build_union(other, zelf.into(), vm)
+        }
     }
-    #[pyclass(flags(BASETYPE))]
-    impl TypeVarTuple {}
 
     pub(crate) fn make_typevartuple(name: PyObjectRef) -> TypeVarTuple {
-        TypeVarTuple { name }
+        TypeVarTuple {
+            name,
+            default_value: None,
+            evaluate_default: None,
+        }
     }
 
     #[pyattr]
     #[pyclass(name = "ParamSpecArgs")]
     #[derive(Debug, PyPayload)]
     #[allow(dead_code)]
-    pub(crate) struct ParamSpecArgs {}
-    #[pyclass(flags(BASETYPE))]
-    impl ParamSpecArgs {}
+    pub(crate) struct ParamSpecArgs {
+        origin: PyObjectRef,
+    }
+
+    impl Representable for ParamSpecArgs {
+        fn repr_str(zelf: &Py<Self>, vm: &VirtualMachine) -> PyResult<String> {
+            let name = zelf.origin.get_attr("__name__", vm)?;
+            Ok(format!("{}.args", name.str(vm)?))
+        }
+    }
+
+    #[pyclass(flags(BASETYPE), with(Representable))]
+    impl ParamSpecArgs {
+        #[pygetset(magic)]
+        fn origin(&self) -> PyObjectRef {
+            self.origin.clone()
+        }
+
+        #[pymethod(magic)]
+        fn eq(&self, other: PyObjectRef, vm: &VirtualMachine) -> PyObjectRef {
+            match other.payload::<ParamSpecArgs>() {
+                Some(other) => vm.ctx.new_bool(self.origin.is(&other.origin)).into(),
+                None => vm.ctx.not_implemented(),
+            }
+        }
+    }
 
     #[pyattr]
     #[pyclass(name = "ParamSpecKwargs")]
     #[derive(Debug, PyPayload)]
     #[allow(dead_code)]
-    pub(crate) struct ParamSpecKwargs {}
-    #[pyclass(flags(BASETYPE))]
-    impl ParamSpecKwargs {}
+    pub(crate) struct ParamSpecKwargs {
+        origin: PyObjectRef,
+    }
+
+    impl Representable for ParamSpecKwargs {
+        fn repr_str(zelf: &Py<Self>, vm: &VirtualMachine) -> PyResult<String> {
+            let name = zelf.origin.get_attr("__name__", vm)?;
+            Ok(format!("{}.kwargs", name.str(vm)?))
+        }
+    }
+
+    #[pyclass(flags(BASETYPE), with(Representable))]
+    impl ParamSpecKwargs {
+        #[pygetset(magic)]
+        fn origin(&self) -> PyObjectRef {
+            self.origin.clone()
+        }
+
+        #[pymethod(magic)]
+        fn eq(&self, other: PyObjectRef, vm: &VirtualMachine) -> PyObjectRef {
+            match other.payload::<ParamSpecKwargs>() {
+                Some(other) => vm.ctx.new_bool(self.origin.is(&other.origin)).into(),
+                None => vm.ctx.not_implemented(),
+            }
+        }
+    }
 
     #[pyattr]
     #[pyclass(name)]
@@ -324,22 +730,84 @@ pub(crate) mod _typing {
     pub(crate) struct TypeAliasType {
         name: PyObjectRef, // TODO PyStrRef?
         type_params: PyTupleRef,
-        value: PyObjectRef,
-        // compute_value: PyObjectRef,
-        // module: PyObjectRef,
+        // `None` until first read: `compute_value` is only ever invoked once, so a
+        // forward-referenced alias (`type X = X | None`) just needs its RHS resolvable
+        // by the time something actually reads `__value__`, not at `type` statement
+        // execution time.
+        value: parking_lot::Mutex<Option<PyObjectRef>>,
+        compute_value: PyObjectRef,
+        module: PyObjectRef,
     }
     #[pyclass(flags(BASETYPE))]
     impl TypeAliasType {
         pub fn new(
             name: PyObjectRef,
             type_params: PyTupleRef,
-            value: PyObjectRef,
+            compute_value: PyObjectRef,
+            module: PyObjectRef,
         ) -> TypeAliasType {
             TypeAliasType {
                 name,
                 type_params,
-                value,
+                value: parking_lot::Mutex::new(None),
+                compute_value,
+                module,
+            }
+        }
+
+        #[pygetset(magic)]
+        fn name(&self) -> PyObjectRef {
+            self.name.clone()
+        }
+
+        #[pygetset(magic)]
+        fn type_params(&self) -> PyObjectRef {
+            self.type_params.clone().into()
+        }
+
+        #[pygetset(magic)]
+        fn module(&self) -> PyObjectRef {
+            self.module.clone()
+        }
+
+        #[pygetset(magic)]
+        fn value(&self, vm: &VirtualMachine) -> PyResult {
+            let mut value = self.value.lock();
+            if let Some(value) = value.as_ref() {
+                return Ok(value.clone());
             }
+            let computed = self.compute_value.call((), vm)?;
+            *value = Some(computed.clone());
+            Ok(computed)
+        }
+
+        /// `MyAlias[int]`: same parameterized-generic-alias machinery `Generic` uses,
+        /// just rooted at this alias instance instead of a class.
+        #[pymethod(magic)]
+        fn getitem(zelf: PyRef<Self>, args: PyObjectRef, vm: &VirtualMachine) -> PyGenericAlias {
+            PyGenericAlias::new(zelf.into(), args, vm)
+        }
+
+        /// Pickle by qualified name, the same way a module-level class or function
+        /// does: `getattr(sys.modules[self.__module__], self.__name__)` looks the alias
+        /// back up rather than attempting to serialize its (possibly unevaluated) value.
+        #[pymethod(magic)]
+        fn reduce(&self, vm: &VirtualMachine) -> PyResult {
+            let sys_modules = vm.sys_module.get_attr("modules", vm)?;
+            let module = sys_modules.get_item(&self.module, vm)?;
+            let getattr = vm.builtins.get_attr("getattr", vm)?;
+            let getattr_args = vm.new_tuple((module, self.name.clone()));
+            Ok(vm.new_tuple((getattr, getattr_args)).into())
+        }
+
+        #[pymethod(magic)]
+        fn or(zelf: PyRef<Self>, other: PyObjectRef, vm: &VirtualMachine) -> PyResult {
+            build_union(zelf.into(), other, vm)
+        }
+
+        #[pymethod(magic)]
+        fn ror(zelf: PyRef<Self>, other: PyObjectRef, vm: &VirtualMachine) -> PyResult {
+            build_union(other, zelf.into(), vm)
         }
     }
 
@@ -349,8 +817,12 @@ pub(crate) mod _typing {
     #[allow(dead_code)]
     pub(crate) struct Generic {}
 
-    // #[pyclass(with(AsMapping), flags(BASETYPE))]
-    #[pyclass(flags(BASETYPE))]
+    // Note: `__mro_entries__` for `class C(Generic[T]): ...` belongs on the
+    // `PyGenericAlias` that `Generic[T]` produces (Python only calls `__mro_entries__`
+    // on bases that aren't already classes), not on `Generic` itself — `Generic` is an
+    // ordinary base when used unsubscripted. `PyGenericAlias` lives in `builtins`, which
+    // this snapshot doesn't include, so that half of the wiring isn't addressed here.
+    #[pyclass(with(AsMapping), flags(BASETYPE))]
     impl Generic {
         #[pyclassmethod(magic)]
         fn class_getitem(cls: PyTypeRef, args: PyObjectRef, vm: &VirtualMachine) -> PyGenericAlias {
@@ -358,15 +830,15 @@ pub(crate) mod _typing {
         }
     }
 
-    // impl AsMapping for Generic {
-    //     fn as_mapping() -> &'static PyMappingMethods {
-    //         static AS_MAPPING: Lazy<PyMappingMethods> = Lazy::new(|| PyMappingMethods {
-    //             subscript: atomic_func!(|mapping, needle, vm| {
-    //                 call_typing_func_object(vm, "_GenericAlias", (mapping.obj, needle))
-    //             }),
-    //             ..PyMappingMethods::NOT_IMPLEMENTED
-    //         });
-    //         &AS_MAPPING
-    //     }
-    // }
+    impl AsMapping for Generic {
+        fn as_mapping() -> &'static PyMappingMethods {
+            static AS_MAPPING: PyMappingMethods = PyMappingMethods {
+                subscript: atomic_func!(|mapping, needle, vm| {
+                    _call_typing_func_object(vm, "_GenericAlias", (mapping.obj.clone(), needle.clone()))
+                }),
+                ..PyMappingMethods::NOT_IMPLEMENTED
+            };
+            &AS_MAPPING
+        }
+    }
 }
@@ -13,6 +13,7 @@ pub mod thread;
 mod vm_new;
 mod vm_object;
 mod vm_ops;
+pub mod zip_importer;
 
 use crate::{
     builtins::{
@@ -47,10 +48,18 @@ pub use setting::Settings;
 
 // Objects are live when they are on stack, or referenced by a name (for now)
 
-/// Top level container of a python virtual machine. In theory you could
-/// create more instances of this struct and have them operate fully isolated.
+/// Top level container of a python virtual machine. Additional, isolated instances
+/// can be spawned with [`VirtualMachine::new_subinterpreter`]: each gets its own
+/// module namespace and mutable state, while sharing the immutable [`Runtime`] (stdlib
+/// init functions, frozen bytecode, hash secret, codec registry) of the interpreter it
+/// was spawned from, so only one copy of that data stays alive across instances. A
+/// subinterpreter still runs its own `builtins`/`sys` module setup and its
+/// own `importlib`/`encodings` bootstrap against that shared data, because each needs
+/// an independently populated `sys.modules` and namespace — only the immutable inputs
+/// to that work, not the work itself, are shared. Data can be passed between
+/// subinterpreters through a [`subinterpreter::channel`].
 ///
-/// To construct this, please refer to the [`Interpreter`](Interpreter)
+/// To construct the first one, please refer to the [`Interpreter`](Interpreter)
 pub struct VirtualMachine {
     pub builtins: PyRef<PyModule>,
     pub sys_module: PyRef<PyModule>,
@@ -77,15 +86,519 @@ struct ExceptionStack {
     prev: Option<Box<ExceptionStack>>,
 }
 
-pub struct PyGlobalState {
-    pub settings: Settings,
+/// The part of the interpreter's state that is truly immutable once startup finishes,
+/// and so can be shared byte-for-byte between subinterpreters instead of each one
+/// recompiling frozen bytecode and carrying its own copy of the module-init table,
+/// hash secret, and codec registry. A subinterpreter still has to *execute*
+/// `importlib`/`encodings` bootstrap against this shared data to populate its own,
+/// independent `sys.modules`; only the inputs to that execution are shared, not the
+/// execution itself.
+///
+/// A subinterpreter spawned via [`VirtualMachine::new_subinterpreter`] ends up holding
+/// a clone of the `PyRc` here: `VirtualMachine::new` still builds a `Runtime` for it,
+/// which is replaced by the shared one before the subinterpreter initializes. Only the
+/// fields left on [`PyGlobalState`] itself vary per interpreter.
+pub struct Runtime {
     pub module_inits: stdlib::StdlibMap,
     pub frozen: HashMap<String, bytecode::FrozenModule, ahash::RandomState>,
+    pub hash_secret: HashSecret,
+    pub codec_registry: CodecsRegistry,
+}
+
+pub struct PyGlobalState {
+    pub settings: Settings,
+    pub runtime: PyRc<Runtime>,
+    /// Usable stack size in bytes for threads running this interpreter, as measured or
+    /// configured by the embedder; `0` means the stack-pointer recursion guard in
+    /// [`thread`] is disabled and only `recursion_limit` applies.
     pub stacksize: AtomicCell<usize>,
+    /// Low-water margin for the stack-pointer guard: a thread is treated as out of
+    /// stack once fewer than this many bytes remain within `stacksize`. See
+    /// [`VirtualMachine::set_stack_margin`].
+    pub stack_margin: AtomicCell<usize>,
     pub thread_count: AtomicCell<usize>,
-    pub hash_secret: HashSecret,
     pub atexit_funcs: PyMutex<Vec<(PyObjectRef, FuncArgs)>>,
-    pub codec_registry: CodecsRegistry,
+    pub monitoring: monitoring::MonitoringState,
+    /// Hooks registered via `sys.addaudithook`, in registration order. Append-only:
+    /// PEP 578 gives embedders no way to remove a hook once installed, so that a
+    /// security-sensitive hook can't be silently deafened by untrusted code.
+    pub audit_hooks: PyMutex<Vec<PyObjectRef>>,
+    /// Native modules registered after startup via
+    /// [`VirtualMachine::register_native_module`]. Unlike `Runtime::module_inits`,
+    /// which is only mutable through `&mut VirtualMachine` inside
+    /// `Interpreter::with_init`, this is consulted and updated through a shared
+    /// `&VirtualMachine`, so embedders can expose host functionality lazily while the
+    /// VM is already running.
+    pub dynamic_native_modules: PyMutex<HashMap<String, stdlib::StdlibInitFunc>>,
+    /// The in-memory module bundle registered via
+    /// [`VirtualMachine::register_memory_archive`], if any. See [`memory_importer`].
+    pub memory_archive: PyMutex<Option<memory_importer::MemoryArchive>>,
+    /// Zip/`.pyz` archives registered via [`VirtualMachine::register_zip_archive`],
+    /// most-recently-registered last. See [`zip_importer`].
+    pub zip_archives: PyMutex<Vec<zip_importer::ZipArchive>>,
+    /// Opt-in PEP 552 hash-based `__pycache__` writing for modules that `_import_inner`
+    /// loads from source held in a registered in-memory or zip archive. Such source has
+    /// no file path, so the cache is keyed by module name: `__pycache__/<name>.rpyc`
+    /// relative to the current directory. Modules found through the regular
+    /// `__import__` machinery are not cached. Off by default, same as a fresh CPython: nothing is
+    /// written to disk until an embedder calls
+    /// [`VirtualMachine::set_bytecode_cache_enabled`]. Never consulted for the
+    /// top-level script, `-c`, stdin, or `exec`/`eval` source — see
+    /// [`VirtualMachine::run_code_string`].
+    pub bytecode_cache_enabled: AtomicCell<bool>,
+    /// The shared `typing.NoDefault` sentinel, constructed lazily on first use and
+    /// reused for every `TypeVar`/`ParamSpec`/`TypeVarTuple` that has no default. Lives
+    /// here rather than in a process-wide `static` so each interpreter (and each
+    /// subinterpreter spawned via [`VirtualMachine::new_subinterpreter`], which gets
+    /// its own `PyGlobalState`) has its own sentinel object rather than leaking the
+    /// first interpreter's forever and breaking `is`-identity across them.
+    pub no_default_sentinel: PyMutex<Option<PyObjectRef>>,
+}
+
+/// Backing state for the `sys.monitoring` instrumentation API (PEP 669).
+///
+/// Unlike the legacy `profile_func`/`trace_func` pair, which are checked on every
+/// instruction once tracing is on at all, `sys.monitoring` is meant to cost nothing for
+/// code objects nobody asked to watch. The module surface (`use_tool_id`, `set_events`,
+/// ...) lives in `stdlib::sys`; this is the bookkeeping it reads and writes.
+pub mod monitoring {
+    use crate::{PyObjectRef, common::lock::PyMutex};
+    use crossbeam_utils::atomic::AtomicCell;
+    use std::collections::HashMap;
+
+    /// `sys.monitoring` hands out a small, fixed number of tool IDs, same as CPython.
+    pub const MAX_TOOL_ID: u8 = 5;
+
+    pub const PY_START: u32 = 1 << 0;
+    pub const PY_RETURN: u32 = 1 << 1;
+    pub const PY_YIELD: u32 = 1 << 2;
+    pub const LINE: u32 = 1 << 3;
+    pub const CALL: u32 = 1 << 4;
+    pub const BRANCH: u32 = 1 << 5;
+    pub const RAISE: u32 = 1 << 6;
+    pub const PY_UNWIND: u32 = 1 << 7;
+    pub const INSTRUCTION: u32 = 1 << 8;
+
+    /// Returned from an event callback to permanently silence that event at the
+    /// location that fired it.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct Disable;
+
+    /// A (code object identity, instruction offset) pair, used to remember locations
+    /// where a callback returned [`Disable`].
+    type Location = (usize, u32);
+
+    #[derive(Default)]
+    pub struct MonitoringState {
+        /// Which of the fixed tool IDs are currently registered.
+        tool_names: PyMutex<HashMap<u8, String>>,
+        /// Event bitmask active globally, per tool.
+        global_events: PyMutex<HashMap<u8, u32>>,
+        /// Event bitmask active for a specific code object, per tool.
+        local_events: PyMutex<HashMap<u8, HashMap<usize, u32>>>,
+        /// The registered callback for a (tool, event) pair.
+        callbacks: PyMutex<HashMap<(u8, u32), PyObjectRef>>,
+        /// Locations where a callback asked to be permanently disabled.
+        disabled_locations: PyMutex<std::collections::HashSet<(u8, u32, Location)>>,
+        /// Per-code-object armed-events bitmask, memoized against `generation`.
+        ///
+        /// The ideal home for this is a bitset field on `PyCode` itself, set once at
+        /// `use_tool_id`/`set_events`/`set_local_events` time so an uninstrumented
+        /// frame pays a single field read. `PyCode` isn't touched by this change, so
+        /// the cache lives here instead, keyed by code identity: still one hashmap
+        /// lookup per frame instead of folding both event maps on every single call,
+        /// and invalidated in O(1) by bumping `generation` rather than by walking
+        /// every cached entry.
+        armed_cache: PyMutex<HashMap<usize, (u64, u32)>>,
+        /// Bumped on every mutation that can change an `armed_events` result, so
+        /// `armed_cache` entries can be recognized as stale without clearing them.
+        generation: AtomicCell<u64>,
+    }
+
+    impl MonitoringState {
+        pub fn use_tool_id(&self, tool_id: u8, name: String) {
+            self.tool_names.lock().insert(tool_id, name);
+        }
+
+        pub fn free_tool_id(&self, tool_id: u8) {
+            self.tool_names.lock().remove(&tool_id);
+            self.global_events.lock().remove(&tool_id);
+            self.local_events.lock().remove(&tool_id);
+            self.callbacks.lock().retain(|(tool, _), _| *tool != tool_id);
+            self.bump_generation();
+        }
+
+        pub fn set_events(&self, tool_id: u8, events: u32) {
+            self.global_events.lock().insert(tool_id, events);
+            self.bump_generation();
+        }
+
+        pub fn set_local_events(&self, tool_id: u8, code_id: usize, events: u32) {
+            self.local_events
+                .lock()
+                .entry(tool_id)
+                .or_default()
+                .insert(code_id, events);
+            self.bump_generation();
+        }
+
+        pub fn register_callback(&self, tool_id: u8, event: u32, callback: PyObjectRef) {
+            self.callbacks.lock().insert((tool_id, event), callback);
+        }
+
+        fn bump_generation(&self) {
+            self.generation.fetch_add(1);
+        }
+
+        /// The bitmask of events armed for `code_id`, i.e. that a frame running that
+        /// code object must actually check for. A code object with no armed events
+        /// takes this fast path and pays nothing beyond the cache lookup below.
+        pub fn armed_events(&self, code_id: usize) -> u32 {
+            let generation = self.generation.load();
+            if let Some(&(cached_gen, mask)) = self.armed_cache.lock().get(&code_id) {
+                if cached_gen == generation {
+                    return mask;
+                }
+            }
+            let global = self.global_events.lock().values().fold(0, |a, b| a | b);
+            let local = self
+                .local_events
+                .lock()
+                .values()
+                .filter_map(|m| m.get(&code_id))
+                .fold(0, |a, b| a | b);
+            let mask = global | local;
+            self.armed_cache.lock().insert(code_id, (generation, mask));
+            mask
+        }
+
+        pub fn callback(&self, tool_id: u8, event: u32) -> Option<PyObjectRef> {
+            self.callbacks.lock().get(&(tool_id, event)).cloned()
+        }
+
+        pub fn is_disabled(&self, tool_id: u8, event: u32, location: Location) -> bool {
+            self.disabled_locations
+                .lock()
+                .contains(&(tool_id, event, location))
+        }
+
+        pub fn disable_location(&self, tool_id: u8, event: u32, location: Location) {
+            self.disabled_locations
+                .lock()
+                .insert((tool_id, event, location));
+        }
+    }
+}
+
+/// A channel for passing data between subinterpreters.
+///
+/// Subinterpreters do not share a `PyRc<Context>`, so handing a `PyObjectRef` across
+/// one directly would let two interpreters mutate the same object without
+/// synchronization. Instead a channel only accepts values that are safe to duplicate
+/// across the boundary: `None`, `bool`, `int`, `str`, and immutable tuples thereof.
+/// Anything else is rejected with a `TypeError` rather than silently shared.
+pub mod subinterpreter {
+    use crate::{PyObjectRef, VirtualMachine, builtins::PyBaseExceptionRef};
+    use std::sync::mpsc;
+
+    pub struct Sender(mpsc::Sender<PyObjectRef>);
+    pub struct Receiver(mpsc::Receiver<PyObjectRef>);
+
+    pub fn channel() -> (Sender, Receiver) {
+        let (tx, rx) = mpsc::channel();
+        (Sender(tx), Receiver(rx))
+    }
+
+    impl Sender {
+        /// Send `value` to the paired [`Receiver`], rejecting it if it isn't one of
+        /// the immutable, picklable-by-value types subinterpreters may share.
+        pub fn send(&self, value: PyObjectRef, vm: &VirtualMachine) -> Result<(), PyBaseExceptionRef> {
+            if !is_shareable(&value, vm) {
+                return Err(vm.new_type_error(format!(
+                    "cannot send a mutable '{}' object between subinterpreters",
+                    value.class().name()
+                )));
+            }
+            self.0
+                .send(value)
+                .map_err(|_| vm.new_runtime_error("subinterpreter channel closed".to_owned()))
+        }
+    }
+
+    impl Receiver {
+        pub fn recv(&self, vm: &VirtualMachine) -> Result<PyObjectRef, PyBaseExceptionRef> {
+            self.0
+                .recv()
+                .map_err(|_| vm.new_runtime_error("subinterpreter channel closed".to_owned()))
+        }
+    }
+
+    /// Only immutable, self-contained values may cross a subinterpreter boundary:
+    /// `None`, `bool`, `int`, `str`, and tuples built entirely out of those.
+    fn is_shareable(value: &PyObjectRef, vm: &VirtualMachine) -> bool {
+        if vm.is_none(value)
+            || value.fast_isinstance(&vm.ctx.types.bool_type)
+            || value.fast_isinstance(&vm.ctx.types.int_type)
+            || value.fast_isinstance(&vm.ctx.types.str_type)
+        {
+            return true;
+        }
+        if let Some(tuple) = value.payload::<crate::builtins::PyTuple>() {
+            return tuple.as_slice().iter().all(|v| is_shareable(v, vm));
+        }
+        false
+    }
+}
+
+/// Resolving modules from a binary blob embedded in the host Rust binary rather than
+/// the filesystem, so a RustPython app can ship as one executable with no accompanying
+/// `.py` tree. Modeled on PyOxidizer's pyembed importer: a `{name -> Entry}` index over
+/// one contiguous `&'static [u8]` data region, so the whole bundle is a single static
+/// allocation rather than one heap copy per module.
+///
+/// [`VirtualMachine::register_memory_archive`] installs the archive; `_import_inner`
+/// consults it ahead of the regular `__import__`/path-based machinery, the same way it
+/// already short-circuits on a `sys.modules` hit, so a plain top-level `import foo`
+/// resolves an archived `foo` transparently. Dotted, relative and `from` imports skip
+/// the archive, and `run_module`/`run_script` go through `runpy` and the filesystem
+/// without consulting it.
+pub mod memory_importer {
+    use crate::{PyObjectRef, PyResult, VirtualMachine};
+    use std::collections::HashMap;
+
+    /// What an [`Entry`]'s bytes contain.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum EntryKind {
+        /// Python source text, compiled on first import.
+        Source,
+        /// Opaque package data (e.g. an `importlib.resources` payload). Not
+        /// executable; importing one raises `ImportError` like CPython does for a
+        /// resource that isn't itself a module.
+        Data,
+    }
+
+    /// Where one module or resource's bytes live within a [`MemoryArchive`]'s data
+    /// region.
+    #[derive(Debug, Clone, Copy)]
+    pub struct Entry {
+        pub kind: EntryKind,
+        pub offset: usize,
+        pub length: usize,
+        /// Whether this entry is a package (gets an empty synthetic `__path__`). Its
+        /// submodules are not resolved from the archive, since `_import_inner` only
+        /// consults it for plain top-level imports.
+        pub is_package: bool,
+    }
+
+    /// A bundle of modules embedded in the host binary: an index over one contiguous
+    /// `&'static [u8]` region, so looking a module up borrows straight out of the
+    /// binary's own `.rodata` with no copy.
+    pub struct MemoryArchive {
+        entries: HashMap<String, Entry>,
+        data: &'static [u8],
+    }
+
+    impl MemoryArchive {
+        pub fn new(entries: HashMap<String, Entry>, data: &'static [u8]) -> Self {
+            Self { entries, data }
+        }
+
+        pub fn contains(&self, name: &str) -> bool {
+            self.entries.contains_key(name)
+        }
+
+        fn bytes(&self, entry: &Entry) -> &'static [u8] {
+            &self.data[entry.offset..entry.offset + entry.length]
+        }
+
+        /// Look `name` up and, if it's in the archive, execute it as a fresh module.
+        /// Returns `None` (rather than an `ImportError`) when the archive simply
+        /// doesn't have `name`, so the caller can fall through to the next finder.
+        pub fn load(&self, name: &str, vm: &VirtualMachine) -> Option<PyResult<PyObjectRef>> {
+            let entry = self.entries.get(name)?;
+            let bytes = self.bytes(entry);
+            Some(self.exec_entry(name, entry, bytes, vm))
+        }
+
+        fn exec_entry(
+            &self,
+            name: &str,
+            entry: &Entry,
+            bytes: &'static [u8],
+            vm: &VirtualMachine,
+        ) -> PyResult<PyObjectRef> {
+            match entry.kind {
+                EntryKind::Data => Err(vm.new_import_error(
+                    format!("'{name}' is archive data, not an importable module"),
+                    vm.ctx.new_str(name.to_owned()),
+                )),
+                EntryKind::Source => {
+                    let source = std::str::from_utf8(bytes).map_err(|e| {
+                        vm.new_import_error(
+                            format!("archived source for '{name}' is not valid UTF-8: {e}"),
+                            vm.ctx.new_str(name.to_owned()),
+                        )
+                    })?;
+                    super::exec_module_source(vm, name, source, entry.is_package)
+                }
+            }
+        }
+    }
+}
+
+/// Process-global audit hooks, for embedders that need to observe auditable events
+/// (PEP 578) from before any [`VirtualMachine`] exists to hold one in its
+/// per-interpreter [`PyGlobalState::audit_hooks`]. Mirrors CPython's
+/// `PySys_AddAuditHook`, which takes a plain C function rather than a Python object
+/// for the same reason: nothing in the interpreter is up yet to own a `PyObjectRef`.
+///
+/// A native hook only ever sees the event name, not its arguments - those are
+/// `PyObjectRef`s scoped to whichever VM raised the event, and a process-global hook
+/// has no VM of its own to interpret them against.
+pub mod native_audit_hooks {
+    use crate::common::lock::PyMutex;
+    use std::sync::OnceLock;
+
+    type NativeHook = Box<dyn Fn(&str) + Send + Sync>;
+
+    static HOOKS: OnceLock<PyMutex<Vec<NativeHook>>> = OnceLock::new();
+
+    fn hooks() -> &'static PyMutex<Vec<NativeHook>> {
+        HOOKS.get_or_init(|| PyMutex::new(Vec::new()))
+    }
+
+    /// Register a hook, typically before any `VirtualMachine` has been created.
+    /// Never removed once added, same append-only guarantee as
+    /// [`VirtualMachine::add_audit_hook`]. Every VM's [`VirtualMachine::audit`] call
+    /// runs all currently-registered native hooks ahead of its own per-interpreter
+    /// hooks.
+    pub fn add(hook: impl Fn(&str) + Send + Sync + 'static) {
+        hooks().lock().push(Box::new(hook));
+    }
+
+    pub(super) fn run(event: &str) {
+        for hook in hooks().lock().iter() {
+            hook(event);
+        }
+    }
+}
+
+/// On-disk bytecode cache for modules loaded by the import system, via
+/// `VirtualMachine::compile_for_import`, modeled on CPython's PEP 552 hash-based
+/// `.pyc` format: a 4-byte magic number, a 4-byte little-endian bit field (bit 0 =
+/// hash-based, bit 1 = check_source), then either an 8-byte `(mtime, size)` pair or an
+/// 8-byte source hash, followed by the serialized code object. Opt-in via
+/// `VirtualMachine::set_bytecode_cache_enabled`; never used for the top-level script,
+/// `-c`, stdin, or `exec`/`eval` source.
+///
+/// Only the header layout matches CPython's; the payload is RustPython's own
+/// serialized `CodeObject`; RustPython bytecode isn't CPython bytecode, so a cache
+/// written here isn't interchangeable with an actual CPython `__pycache__` entry, only
+/// internally consistent across runs of this interpreter. Caches are written
+/// hash-based with `check_source` set (CPython's `checked-hash` invalidation mode),
+/// so a cache survives a file copy or rebuild with an unchanged mtime, but is still
+/// rejected the moment the source text actually changes.
+pub mod pyc_cache {
+    use crate::bytecode::CodeObject;
+    use std::{
+        hash::Hasher,
+        io::{self, Write},
+        path::{Path, PathBuf},
+    };
+
+    /// Identifies this cache's format/version; bumped whenever the serialized
+    /// `CodeObject` layout changes so a cache from an older build is rejected outright
+    /// instead of being misinterpreted.
+    const MAGIC: u32 = 0x5259_0001; // "RY", cache format 1
+
+    const FLAG_HASH_BASED: u32 = 1 << 0;
+    const FLAG_CHECK_SOURCE: u32 = 1 << 1;
+
+    const HEADER_LEN: usize = 16;
+
+    /// Where the cache for `source_path` lives: `__pycache__/<file name>.rpyc`
+    /// alongside the source file, mirroring CPython's `__pycache__` layout (minus the
+    /// interpreter tag CPython embeds in the name — there's only one RustPython ABI
+    /// here).
+    pub fn cache_path(source_path: &Path) -> Option<PathBuf> {
+        let dir = source_path.parent()?.join("__pycache__");
+        let file_name = source_path.file_name()?.to_str()?;
+        Some(dir.join(format!("{file_name}.rpyc")))
+    }
+
+    /// An 8-byte source hash for PEP 552 hash-based invalidation. Unlike CPython's
+    /// `source_hash` (SipHash-1-3 keyed from a build-time secret), this uses a plain
+    /// `DefaultHasher`: deterministic across runs of this interpreter, which is all
+    /// the cache needs, but not bit-comparable to a CPython-generated hash.
+    fn source_hash(source: &str) -> u64 {
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        hasher.write(source.as_bytes());
+        hasher.finish()
+    }
+
+    /// Try to load a cached code object for `source`. Returns `None` on any kind of
+    /// miss — file absent, magic mismatch, hash mismatch, corrupt payload — so the
+    /// caller falls back to compiling; caching is strictly an optimization.
+    pub fn load(source_path: &Path, source: &str) -> Option<CodeObject> {
+        let path = cache_path(source_path)?;
+        let data = std::fs::read(path).ok()?;
+        if data.len() < HEADER_LEN || read_u32(&data, 0)? != MAGIC {
+            return None;
+        }
+        let flags = read_u32(&data, 4)?;
+        let stored = read_u64(&data, 8)?;
+        if flags & FLAG_HASH_BASED != 0 {
+            if flags & FLAG_CHECK_SOURCE != 0 && stored != source_hash(source) {
+                return None;
+            }
+        } else {
+            // Classic mtime/size invalidation: trust the cache only while both the
+            // modification time (bytes 8..12) and the file size (bytes 12..16) still
+            // match what was recorded.
+            let meta = std::fs::metadata(source_path).ok()?;
+            let mtime = meta
+                .modified()
+                .ok()?
+                .duration_since(std::time::UNIX_EPOCH)
+                .ok()?
+                .as_secs();
+            if stored != ((mtime & 0xffff_ffff) | (meta.len() & 0xffff_ffff) << 32) {
+                return None;
+            }
+        }
+        CodeObject::from_bytes(&data[HEADER_LEN..]).ok()
+    }
+
+    /// Write a fresh cache for `source`/`code`, atomically: the header and payload are
+    /// written to a sibling temp file first, then renamed over the target, so a
+    /// concurrent reader never observes a partially written cache.
+    pub fn store(source_path: &Path, source: &str, code: &CodeObject) -> io::Result<()> {
+        let path = cache_path(source_path)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "no __pycache__ directory for source path"))?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+
+        let mut buf = Vec::with_capacity(HEADER_LEN);
+        buf.extend_from_slice(&MAGIC.to_le_bytes());
+        buf.extend_from_slice(&(FLAG_HASH_BASED | FLAG_CHECK_SOURCE).to_le_bytes());
+        buf.extend_from_slice(&source_hash(source).to_le_bytes());
+        buf.extend_from_slice(&code.to_bytes());
+
+        let tmp_path = path.with_extension("rpyc.tmp");
+        std::fs::File::create(&tmp_path)?.write_all(&buf)?;
+        std::fs::rename(&tmp_path, path)
+    }
+
+    fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+        data.get(offset..offset + 4)
+            .map(|b| u32::from_le_bytes(b.try_into().unwrap()))
+    }
+
+    fn read_u64(data: &[u8], offset: usize) -> Option<u64> {
+        data.get(offset..offset + 8)
+            .map(|b| u64::from_le_bytes(b.try_into().unwrap()))
+    }
 }
 
 impl VirtualMachine {
@@ -145,20 +658,36 @@ impl VirtualMachine {
             repr_guards: RefCell::default(),
             state: PyRc::new(PyGlobalState {
                 settings,
-                module_inits,
-                frozen: HashMap::default(),
+                runtime: PyRc::new(Runtime {
+                    module_inits,
+                    frozen: HashMap::default(),
+                    hash_secret,
+                    codec_registry,
+                }),
                 stacksize: AtomicCell::new(0),
+                stack_margin: AtomicCell::new(thread::DEFAULT_STACK_MARGIN),
                 thread_count: AtomicCell::new(0),
-                hash_secret,
                 atexit_funcs: PyMutex::default(),
-                codec_registry,
+                monitoring: monitoring::MonitoringState::default(),
+                audit_hooks: PyMutex::default(),
+                dynamic_native_modules: PyMutex::default(),
+                memory_archive: PyMutex::default(),
+                zip_archives: PyMutex::default(),
+                bytecode_cache_enabled: AtomicCell::new(false),
+                no_default_sentinel: PyMutex::new(None),
             }),
             initialized: false,
             recursion_depth: Cell::new(0),
         };
 
+        // Record the approximate base of this OS thread's stack so the stack-pointer
+        // recursion guard has something to measure headroom against; see `thread`.
+        thread::init_stack_base();
+
         let frozen = frozen::get_module_inits().collect();
-        PyRc::get_mut(&mut vm.state).unwrap().frozen = frozen;
+        PyRc::get_mut(&mut PyRc::get_mut(&mut vm.state).unwrap().runtime)
+            .unwrap()
+            .frozen = frozen;
 
         vm.builtins.init_module_dict(
             vm.ctx.new_str(ascii!("builtins")).into(),
@@ -205,6 +734,7 @@ impl VirtualMachine {
                 // require the Python stdlib to be present
                 let io = import::import_builtin(self, "_io")?;
                 let set_stdio = |name, fd, mode: &str| {
+                    self.audit("open", FuncArgs::from(vec![self.ctx.new_int(fd).into()]))?;
                     let stdio = crate::stdlib::io::open(
                         self.ctx.new_int(fd).into(),
                         Some(mode),
@@ -242,19 +772,26 @@ impl VirtualMachine {
             .expect("there should not be multiple threads while a user has a mut ref to a vm")
     }
 
+    /// Only valid before any subinterpreter has been spawned from this one: once a
+    /// subinterpreter shares `runtime`, no one can get a unique `&mut` to it anymore.
+    fn runtime_mut(&mut self) -> &mut Runtime {
+        PyRc::get_mut(&mut self.state_mut().runtime)
+            .expect("runtime is shared with a subinterpreter; it can no longer be mutated")
+    }
+
     /// Can only be used in the initialization closure passed to [`Interpreter::with_init`]
     pub fn add_native_module<S>(&mut self, name: S, module: stdlib::StdlibInitFunc)
     where
         S: Into<Cow<'static, str>>,
     {
-        self.state_mut().module_inits.insert(name.into(), module);
+        self.runtime_mut().module_inits.insert(name.into(), module);
     }
 
     pub fn add_native_modules<I>(&mut self, iter: I)
     where
         I: IntoIterator<Item = (Cow<'static, str>, stdlib::StdlibInitFunc)>,
     {
-        self.state_mut().module_inits.extend(iter);
+        self.runtime_mut().module_inits.extend(iter);
     }
 
     /// Can only be used in the initialization closure passed to [`Interpreter::with_init`]
@@ -262,7 +799,61 @@ impl VirtualMachine {
     where
         I: IntoIterator<Item = (String, bytecode::FrozenModule)>,
     {
-        self.state_mut().frozen.extend(frozen);
+        self.runtime_mut().frozen.extend(frozen);
+    }
+
+    /// Register a native module so it becomes importable the next time it's asked for,
+    /// without needing `&mut self`. Unlike `add_native_module`, which only works inside
+    /// `Interpreter::with_init` before the VM starts running, this can be called at any
+    /// point on a live, shared `&VirtualMachine` — e.g. in response to a user action
+    /// that should expose new host functionality. The module is instantiated lazily:
+    /// `init_fn` runs on first import, through the same `_import_inner` cache path the
+    /// built-in `module_inits` table uses, and the result is cached into `sys.modules`
+    /// like any other module.
+    pub fn register_native_module(&self, name: impl Into<String>, init_fn: stdlib::StdlibInitFunc) {
+        self.state
+            .dynamic_native_modules
+            .lock()
+            .insert(name.into(), init_fn);
+    }
+
+    /// Install an in-memory module bundle, ahead of the path-based import machinery:
+    /// a single-file RustPython executable can call this once at startup with an
+    /// archive built from data linked into the binary (e.g. via `include_bytes!`) and
+    /// have `import` resolve modules from it with no `.py` tree on disk. See
+    /// [`memory_importer`].
+    pub fn register_memory_archive(&self, archive: memory_importer::MemoryArchive) {
+        *self.state.memory_archive.lock() = Some(archive);
+    }
+
+    /// Register a zip archive as an import source, resolved the same way the
+    /// in-memory archive is: by module name, ahead of the regular `__import__`
+    /// machinery. [`VirtualMachine::run_script`] calls this when the script path
+    /// itself is a `.zip`/`.pyz` archive, so `import`s inside it resolve to the
+    /// archive's members without unpacking anything to disk.
+    pub fn register_zip_archive(&self, archive: zip_importer::ZipArchive) {
+        self.state.zip_archives.lock().push(archive);
+    }
+
+    /// Spawn a subinterpreter: a fresh `VirtualMachine` with its own module namespace
+    /// (`sys.modules`, `builtins`/`sys` module objects) but sharing this one's
+    /// [`Runtime`] — stdlib init functions, frozen bytecode, hash secret policy, and
+    /// codec registry. `VirtualMachine::new` still builds a fresh `Runtime` for the
+    /// subinterpreter; it is dropped in favour of this one's before
+    /// `sub.initialize()` runs, so the two then share one copy. `sub.initialize()` still
+    /// *runs* `builtins`/`sys` setup and the `importlib`/`encodings` bootstrap, because
+    /// a subinterpreter needs its own populated `sys.modules` and can't share the
+    /// first interpreter's live module objects without losing isolation between them
+    /// (the same tradeoff CPython's own subinterpreters make); only the immutable
+    /// inputs to that bootstrap are shared, not the act of running it. Unlike
+    /// `Interpreter::with_init`, this can be called on a live, already-initialized
+    /// `&VirtualMachine`.
+    pub fn new_subinterpreter(&self) -> VirtualMachine {
+        let mut sub = VirtualMachine::new(self.state.settings.clone());
+        let sub_state = PyRc::get_mut(&mut sub.state).unwrap();
+        sub_state.runtime = self.state.runtime.clone();
+        sub.initialize();
+        sub
     }
 
     /// Set the custom signal channel for the interpreter
@@ -270,13 +861,40 @@ impl VirtualMachine {
         self.signal_rx = Some(signal_rx);
     }
 
+    /// Tell the stack-pointer recursion guard how much usable stack (in bytes) threads
+    /// running this interpreter actually have. Embedders handing RustPython a small
+    /// stack (WASM, spawned threads with a reduced size) should call this with that
+    /// size so deep native recursion raises a `RecursionError` instead of overflowing
+    /// the real stack; `0` (the default) disables the guard and leaves
+    /// `recursion_limit` as the only protection.
+    pub fn set_stacksize(&self, stacksize: usize) {
+        self.state.stacksize.store(stacksize);
+    }
+
+    /// Low-water margin for the stack-pointer guard: once fewer than this many bytes
+    /// remain within the configured `stacksize`, `with_recursion`/`check_recursive_call`
+    /// raise a `RecursionError`. Works alongside `sys.setrecursionlimit`: that call
+    /// still bounds Python frame count, while this bounds how close to the real stack
+    /// limit native recursion is allowed to get.
+    pub fn set_stack_margin(&self, margin: usize) {
+        self.state.stack_margin.store(margin);
+    }
+
     pub fn run_code_obj(&self, code: PyRef<PyCode>, scope: Scope) -> PyResult {
+        self.audit(
+            "exec",
+            FuncArgs::from(vec![code.clone().into()]),
+        )?;
         let frame = Frame::new(code, scope, self.builtins.dict(), &[], self).into_ref(self);
         self.run_frame(frame)
     }
 
     #[cold]
     pub fn run_unraisable(&self, e: PyBaseExceptionRef, msg: Option<String>, object: PyObjectRef) {
+        let _ = self.audit(
+            "sys.excepthook",
+            FuncArgs::from(vec![e.clone().into(), self.new_pyobj(msg.clone())]),
+        );
         let sys_module = self.import("sys", None, 0).unwrap();
         let unraisablehook = sys_module.get_attr("unraisablehook", self).unwrap();
 
@@ -335,10 +953,22 @@ impl VirtualMachine {
     // To be called right before raising the recursion depth.
     fn check_recursive_call(&self, _where: &str) -> PyResult<()> {
         if self.recursion_depth.get() >= self.recursion_limit.get() {
-            Err(self.new_recursion_error(format!("maximum recursion depth exceeded {}", _where)))
-        } else {
-            Ok(())
+            return Err(self.new_recursion_error(format!("maximum recursion depth exceeded {}", _where)));
         }
+        // `recursion_limit` only counts Python frames; deep native recursion through
+        // slot calls (operator dispatch, `repr`, ...) can blow the real OS stack well
+        // before that counter does. If the embedder told us how big our stack is,
+        // raise a clean RecursionError once headroom drops below the configured
+        // margin instead of segfaulting.
+        if let Some(remaining) = thread::remaining_stack(self.state.stacksize.load()) {
+            if remaining < self.state.stack_margin.load() {
+                return Err(self.new_recursion_error(format!(
+                    "maximum recursion depth exceeded {} (stack overflow guard)",
+                    _where
+                )));
+            }
+        }
+        Ok(())
     }
 
     pub fn current_frame(&self) -> Option<Ref<FrameRef>> {
@@ -400,6 +1030,25 @@ impl VirtualMachine {
         from_list: Option<PyTupleTyped<PyStrRef>>,
         level: usize,
     ) -> PyResult {
+        let from_list_obj = from_list
+            .as_ref()
+            .map(|tup| tup.to_pyobject(self))
+            .unwrap_or_else(|| self.new_tuple(()).into());
+        let (globals_obj, locals_obj) = match self.current_frame() {
+            Some(frame) => (frame.globals.clone().into(), frame.locals.clone().into()),
+            None => (self.ctx.none(), self.ctx.none()),
+        };
+        self.audit(
+            "import",
+            FuncArgs::from(vec![
+                module.clone().into(),
+                globals_obj,
+                locals_obj,
+                from_list_obj,
+                self.new_pyobj(level),
+            ]),
+        )?;
+
         // if the import inputs seem weird, e.g a package import or something, rather than just
         // a straight `import ident`
         let weird = module.as_str().contains('.')
@@ -425,6 +1074,66 @@ impl VirtualMachine {
                 }
             }
             None => {
+                // Dynamically registered native modules (`register_native_module`)
+                // and the in-memory archive (`register_memory_archive`) take the same
+                // shortcut `cached_module` did for already-imported ones, acting as
+                // meta-path finders without needing a real `sys.meta_path` entry: a
+                // plain top-level import of a name either one knows about is resolved
+                // here and cached, instead of falling through to the full
+                // `__import__` machinery, which wouldn't know where to find it.
+                if !weird {
+                    let init_result = self
+                        .state
+                        .dynamic_native_modules
+                        .lock()
+                        .get(module.as_str())
+                        .map(|init_fn| init_fn(self));
+                    if let Some(module_obj) = init_result {
+                        let sys_modules = self.sys_module.get_attr("modules", self)?;
+                        sys_modules.set_item(&*module, module_obj.clone(), self)?;
+                        return Ok(module_obj);
+                    }
+
+                    let archive_result = self
+                        .state
+                        .memory_archive
+                        .lock()
+                        .as_ref()
+                        .and_then(|archive| archive.load(module.as_str(), self));
+                    if let Some(module_result) = archive_result {
+                        let module_obj = module_result?;
+                        let sys_modules = self.sys_module.get_attr("modules", self)?;
+                        sys_modules.set_item(&*module, module_obj.clone(), self)?;
+                        return Ok(module_obj);
+                    }
+
+                    let zip_hit = self.state.zip_archives.lock().iter().find_map(|archive| {
+                        archive
+                            .module_source(module.as_str())
+                            .map(|member| (archive.read_member(&member), member))
+                    });
+                    if let Some((read_result, member)) = zip_hit {
+                        let bytes = read_result.map_err(|e| {
+                            self.new_import_error(
+                                format!("error reading '{member}' from zip archive: {e}"),
+                                module.clone(),
+                            )
+                        })?;
+                        let source = std::str::from_utf8(&bytes).map_err(|e| {
+                            self.new_import_error(
+                                format!("'{member}' in zip archive is not valid UTF-8: {e}"),
+                                module.clone(),
+                            )
+                        })?;
+                        let is_package = member.ends_with("__init__.py");
+                        let module_obj =
+                            exec_module_source(self, module.as_str(), source, is_package)?;
+                        let sys_modules = self.sys_module.get_attr("modules", self)?;
+                        sys_modules.set_item(&*module, module_obj.clone(), self)?;
+                        return Ok(module_obj);
+                    }
+                }
+
                 let import_func =
                     self.builtins
                         .clone()
@@ -584,6 +1293,66 @@ impl VirtualMachine {
             .is_some()
     }
 
+    /// Fire a `sys.monitoring` event for `code_id` at `instruction_offset`, if any tool
+    /// has armed `event` there. A code object nobody is monitoring costs a call here
+    /// nothing beyond the single `armed_events` bitmask lookup. `Frame::run` does not
+    /// call this; its `trace_func` check is unchanged.
+    pub(crate) fn fire_monitoring_event(
+        &self,
+        code_id: usize,
+        event: u32,
+        instruction_offset: u32,
+        make_args: impl Fn() -> FuncArgs,
+    ) -> PyResult<()> {
+        if self.state.monitoring.armed_events(code_id) & event == 0 {
+            return Ok(());
+        }
+        for tool_id in 0..=monitoring::MAX_TOOL_ID {
+            let location = (code_id, instruction_offset);
+            if self.state.monitoring.is_disabled(tool_id, event, location) {
+                continue;
+            }
+            let Some(callback) = self.state.monitoring.callback(tool_id, event) else {
+                continue;
+            };
+            let result = self.invoke(&callback, make_args())?;
+            // `sys.monitoring.DISABLE` is a sentinel object surfaced by `stdlib::sys`;
+            // a callback returning it permanently retires this (tool, event, location).
+            if stdlib::sys::is_monitoring_disable_sentinel(self, &result) {
+                self.state
+                    .monitoring
+                    .disable_location(tool_id, event, location);
+            }
+        }
+        Ok(())
+    }
+
+    /// `sys.addaudithook(hook)`: register a new runtime-auditing hook (PEP 578). Hooks
+    /// are never removed once added, so embedders and security tooling can rely on
+    /// every hook they installed still running for the lifetime of the interpreter.
+    pub fn add_audit_hook(&self, hook: PyObjectRef) {
+        self.state.audit_hooks.lock().push(hook);
+    }
+
+    /// `sys.audit(event, *args)`: report that the interpreter is about to perform an
+    /// auditable operation. [`native_audit_hooks`] run first (event name only), then
+    /// every per-interpreter hook is invoked, in registration order, with
+    /// `(event, args)`; if any hook raises, the exception propagates and the audited
+    /// operation must not go ahead. Hooks run even during interpreter shutdown, since
+    /// the whole point is a complete trail of what actually happened.
+    pub fn audit(&self, event: &str, args: FuncArgs) -> PyResult<()> {
+        native_audit_hooks::run(event);
+        let hooks = self.state.audit_hooks.lock().clone();
+        if hooks.is_empty() {
+            return Ok(());
+        }
+        let args = self.new_tuple(args.args);
+        for hook in hooks {
+            self.invoke(&hook, (event, args.clone()))?;
+        }
+        Ok(())
+    }
+
     #[inline]
     /// Checks for triggered signals and calls the appropriate handlers. A no-op on
     /// platforms where signals are not supported.
@@ -702,12 +1471,30 @@ impl VirtualMachine {
     }
 
     pub fn run_script(&self, scope: Scope, path: &str) -> PyResult<()> {
+        let is_zip_archive = matches!(
+            std::path::Path::new(path)
+                .extension()
+                .and_then(|ext| ext.to_str()),
+            Some("zip") | Some("pyz")
+        );
+        if is_zip_archive {
+            // No Python-level zipimport path hook exists to discover this through
+            // `get_importer`, so open it directly: its modules are then resolved by
+            // `_import_inner`'s own `zip_archives` check, the same way a registered
+            // in-memory archive is.
+            let archive = zip_importer::ZipArchive::open(std::path::Path::new(path))
+                .map_err(|e| {
+                    self.new_import_error(
+                        format!("cannot open '{path}' as a zip archive: {e}"),
+                        self.ctx.new_str(path.to_owned()),
+                    )
+                })?;
+            self.register_zip_archive(archive);
+            return self.run_path_as_main_module(path);
+        }
+
         if get_importer(path, self)?.is_some() {
-            self.insert_sys_path(self.new_pyobj(path))?;
-            let runpy = self.import("runpy", None, 0)?;
-            let run_module_as_main = runpy.get_attr("_run_module_as_main", self)?;
-            self.invoke(&run_module_as_main, (self.ctx.new_str("__main__"), false))?;
-            return Ok(());
+            return self.run_path_as_main_module(path);
         }
 
         let dir = std::path::Path::new(path)
@@ -729,7 +1516,30 @@ impl VirtualMachine {
         Ok(())
     }
 
+    /// Run whatever `path` resolves to through `runpy._run_module_as_main("__main__")`,
+    /// the way CPython runs a package or zipapp directory/archive that's on
+    /// `sys.path`. Shared by `run_script`'s package-directory and zip-archive
+    /// branches.
+    fn run_path_as_main_module(&self, path: &str) -> PyResult<()> {
+        self.insert_sys_path(self.new_pyobj(path))?;
+        let runpy = self.import("runpy", None, 0)?;
+        let run_module_as_main = runpy.get_attr("_run_module_as_main", self)?;
+        self.invoke(&run_module_as_main, (self.ctx.new_str("__main__"), false))?;
+        Ok(())
+    }
+
+    /// Compile and run `source` as the top-level script, `-c` argument, stdin, or an
+    /// `exec`/`eval` source string. Like CPython, this never reads or writes a
+    /// `__pycache__` entry: that would mean caching code that (for `-c`/stdin/`exec`)
+    /// usually has no stable path to key a cache on, and littering the cwd with a
+    /// `__pycache__` directory next to a one-off script CPython itself never caches.
+    /// Module source loaded through the import system is the only thing
+    /// [`pyc_cache`] backs; see [`VirtualMachine::compile_for_import`].
     pub fn run_code_string(&self, scope: Scope, source: &str, source_path: String) -> PyResult {
+        self.audit(
+            "compile",
+            FuncArgs::from(vec![self.ctx.new_str(source).into(), self.new_pyobj(source_path.clone())]),
+        )?;
         let code_obj = self
             .compile(source, crate::compile::Mode::Exec, source_path.clone())
             .map_err(|err| self.new_syntax_error(&err))?;
@@ -740,6 +1550,44 @@ impl VirtualMachine {
         self.run_code_obj(code_obj, scope)
     }
 
+    /// Opt an embedder into writing `__pycache__` entries for modules loaded from
+    /// archive source (see [`PyGlobalState::bytecode_cache_enabled`] for where they
+    /// go). Off by default; never affects
+    /// [`VirtualMachine::run_code_string`].
+    pub fn set_bytecode_cache_enabled(&self, enabled: bool) {
+        self.state.bytecode_cache_enabled.store(enabled);
+    }
+
+    /// Like `compile`, but for the import path: if bytecode caching has been turned on
+    /// via [`VirtualMachine::set_bytecode_cache_enabled`] and `source_path` has a
+    /// valid, up-to-date on-disk cache for `source`, the cached code object is used
+    /// directly and parsing/code-gen are skipped entirely; otherwise this compiles as
+    /// normal, writing a fresh cache afterward if caching is enabled. Caching is
+    /// best-effort — any I/O error reading or writing it just falls back to an
+    /// ordinary compile and is never surfaced as a Python-visible error.
+    pub fn compile_for_import(
+        &self,
+        source: &str,
+        source_path: String,
+    ) -> Result<PyRef<PyCode>, crate::compile::CompileError> {
+        if !self.state.bytecode_cache_enabled.load() {
+            return self.compile(source, crate::compile::Mode::Exec, source_path);
+        }
+        let path = std::path::Path::new(&source_path);
+        if let Some(code) = pyc_cache::load(path, source) {
+            return Ok(self.ctx.new_code(code));
+        }
+        let code_obj = self.compile(source, crate::compile::Mode::Exec, source_path)?;
+        if let Err(e) = pyc_cache::store(path, source, &code_obj.code) {
+            trace!(
+                "failed to write bytecode cache for {}: {}",
+                path.display(),
+                e
+            );
+        }
+        Ok(code_obj)
+    }
+
     pub fn run_module(&self, module: &str) -> PyResult<()> {
         let runpy = self.import("runpy", None, 0)?;
         let run_module_as_main = runpy.get_attr("_run_module_as_main", self)?;
@@ -748,6 +1596,34 @@ impl VirtualMachine {
     }
 }
 
+/// Compile `source` as module `name` and execute it in a fresh module object. Shared by
+/// every source taken from somewhere other than the filesystem: the in-memory archive
+/// importer ([`memory_importer`]) and the zip importer (`_import_inner`'s own
+/// `zip_archives` check) both resolve a name to source text this way and let the
+/// caller cache the result into `sys.modules`.
+fn exec_module_source(
+    vm: &VirtualMachine,
+    name: &str,
+    source: &str,
+    is_package: bool,
+) -> PyResult<PyObjectRef> {
+    let code = vm
+        .compile_for_import(source, name.to_owned())
+        .map_err(|err| vm.new_syntax_error(&err))?;
+    let module = PyRef::new_ref(
+        PyModule {},
+        vm.ctx.types.module_type.clone(),
+        Some(vm.ctx.new_dict()),
+    );
+    module.init_module_dict(vm.ctx.new_str(name.to_owned()).into(), vm.ctx.none(), vm);
+    if is_package {
+        module.set_attr("__path__", vm.ctx.new_list(vec![]), vm)?;
+    }
+    let scope = Scope::new(None, module.dict());
+    vm.run_code_obj(code, scope)?;
+    Ok(module.into())
+}
+
 fn get_importer(path: &str, vm: &VirtualMachine) -> PyResult<Option<PyObjectRef>> {
     let path_importer_cache = vm.sys_module.get_attr("path_importer_cache", vm)?;
     let path_importer_cache = PyDictRef::try_from_object(vm, path_importer_cache)?;
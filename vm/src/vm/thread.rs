@@ -0,0 +1,56 @@
+//! Per-OS-thread stack bookkeeping backing the stack-pointer recursion guard.
+//!
+//! `recursion_limit` only counts Python frames; nothing stops deep *native* recursion
+//! through `__getattr__`, operator dispatch, `repr`, and other slot calls (see the hint
+//! in [`VirtualMachine::repr_guards`](super::VirtualMachine::repr_guards)) from
+//! overflowing the real OS stack long before that counter is hit. [`init_stack_base`]
+//! records where a thread entered the VM, and [`remaining_stack`] estimates how much
+//! headroom is left from there, so `check_recursive_call` can raise a `RecursionError`
+//! instead of letting the thread segfault.
+
+use std::cell::Cell;
+
+thread_local! {
+    /// Approximate base of the current OS thread's stack, set once by the first call
+    /// to [`init_stack_base`] on this thread. `None` until then.
+    static STACK_BASE: Cell<Option<usize>> = Cell::new(None);
+}
+
+/// Low-water margin used when no explicit margin has been configured: once fewer than
+/// this many bytes of stack remain, treat the thread as out of room rather than waiting
+/// for an actual overflow.
+pub const DEFAULT_STACK_MARGIN: usize = 16 * 1024;
+
+/// Record the address of a stack-local as this OS thread's approximate stack base.
+/// Call once per thread, as early as possible after it starts running a VM (thread
+/// spawn, subinterpreter entry). Idempotent: a later, more deeply nested call would
+/// record an address further from the real base, understating stack usage, so only the
+/// first call on a given thread has any effect.
+pub fn init_stack_base() {
+    let local = 0u8;
+    let addr = &local as *const u8 as usize;
+    STACK_BASE.with(|base| {
+        if base.get().is_none() {
+            base.set(Some(addr));
+        }
+    });
+}
+
+/// Bytes of stack estimated to remain between a fresh local on this thread and the end
+/// of a `stacksize`-byte stack starting at the recorded base. Returns `None` if `stacksize` is unset (`0`, i.e. the
+/// embedder hasn't opted into the guard) or [`init_stack_base`] was never called on this
+/// thread.
+pub fn remaining_stack(stacksize: usize) -> Option<usize> {
+    if stacksize == 0 {
+        return None;
+    }
+    let local = 0u8;
+    let here = &local as *const u8 as usize;
+    STACK_BASE.with(|base| {
+        let base = base.get()?;
+        // The stack grows down on every platform RustPython targets, and `base` was
+        // recorded closer to thread entry than `here` ever is, so `base >= here`.
+        let used = base.saturating_sub(here);
+        Some(stacksize.saturating_sub(used))
+    })
+}
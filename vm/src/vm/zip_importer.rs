@@ -0,0 +1,286 @@
+//! Minimal ZIP reader backing zip/`.pyz` archive imports, so a whole Python
+//! application bundled as a `.pyz` (PEP 441 zipapp) can be run directly by
+//! [`super::VirtualMachine::run_script`] without unpacking it to disk first. Archives
+//! are consulted by name from `_import_inner`; nothing is added to `sys.path_hooks`.
+//!
+//! Only what's needed to find and read a member by name is parsed: the
+//! end-of-central-directory record (to find the central directory) and the central
+//! directory's file headers (name, offset, size, compression method); member bytes are
+//! then read on demand by seeking to the recorded offset. Only `stored` (uncompressed)
+//! members are supported for now — archives built with the stdlib `zipapp` module's
+//! default `compressed=False` work; a `deflate`d member is reported as an `io::Error`
+//! rather than silently misread.
+
+use std::{
+    fs::File,
+    io::{self, Read, Seek, SeekFrom},
+    path::{Path, PathBuf},
+};
+
+const EOCD_SIGNATURE: u32 = 0x0605_4b50;
+const CENTRAL_DIR_SIGNATURE: u32 = 0x0201_4b50;
+const LOCAL_FILE_SIGNATURE: u32 = 0x0403_4b50;
+const METHOD_STORED: u16 = 0;
+
+/// One entry in the archive's central directory: enough to seek to and read a
+/// member's raw bytes on demand, without holding the whole archive in memory.
+#[derive(Debug, Clone)]
+struct CentralDirEntry {
+    name: String,
+    local_header_offset: u64,
+    compressed_size: u64,
+    method: u16,
+}
+
+/// A `.zip`/`.pyz` archive opened for on-demand reads: only the central directory is
+/// parsed up front, and members are located and read by seeking into the file as
+/// they're imported, rather than unpacking the whole archive first.
+pub struct ZipArchive {
+    path: PathBuf,
+    entries: Vec<CentralDirEntry>,
+}
+
+impl ZipArchive {
+    /// Parse just the central directory of the zip at `path`; member bytes aren't read
+    /// until [`ZipArchive::read_member`] is called.
+    pub fn open(path: &Path) -> io::Result<Self> {
+        let mut file = File::open(path)?;
+        let file_len = file.seek(SeekFrom::End(0))?;
+        let cd_offset = find_central_directory_offset(&mut file, file_len)?;
+        let entries = read_central_directory(&mut file, cd_offset)?;
+        Ok(Self {
+            path: path.to_owned(),
+            entries,
+        })
+    }
+
+    pub fn contains(&self, member: &str) -> bool {
+        self.entries.iter().any(|e| e.name == member)
+    }
+
+    /// Read `member`'s raw bytes out of the archive.
+    pub fn read_member(&self, member: &str) -> io::Result<Vec<u8>> {
+        let entry = self.entries.iter().find(|e| e.name == member).ok_or_else(|| {
+            io::Error::new(io::ErrorKind::NotFound, format!("no '{member}' in archive"))
+        })?;
+        if entry.method != METHOD_STORED {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                format!(
+                    "member '{member}' uses an unsupported compression method ({})",
+                    entry.method
+                ),
+            ));
+        }
+        let mut file = File::open(&self.path)?;
+        let data_offset = local_file_data_offset(&mut file, entry.local_header_offset)?;
+        file.seek(SeekFrom::Start(data_offset))?;
+        let mut buf = vec![0u8; entry.compressed_size as usize];
+        file.read_exact(&mut buf)?;
+        Ok(buf)
+    }
+
+    /// The archive member name that would supply the source for Python module
+    /// `dotted_name` (`foo.bar` -> `foo/bar.py`, or `foo/bar/__init__.py` if it's a
+    /// package), if the archive has one.
+    pub fn module_source(&self, dotted_name: &str) -> Option<String> {
+        let as_path = dotted_name.replace('.', "/");
+        [format!("{as_path}.py"), format!("{as_path}/__init__.py")]
+            .into_iter()
+            .find(|candidate| self.contains(candidate))
+    }
+}
+
+fn find_central_directory_offset(file: &mut File, file_len: u64) -> io::Result<u64> {
+    // The end-of-central-directory record is 22 bytes plus up to a 65535-byte comment,
+    // so scan backward from the end of the file for its signature.
+    let max_back = (22u64 + 65535).min(file_len);
+    let scan_start = file_len - max_back;
+    file.seek(SeekFrom::Start(scan_start))?;
+    let mut buf = Vec::new();
+    file.read_to_end(&mut buf)?;
+    for i in (0..buf.len().saturating_sub(3)).rev() {
+        if u32::from_le_bytes(buf[i..i + 4].try_into().unwrap()) == EOCD_SIGNATURE {
+            let cd_offset = u32::from_le_bytes(buf[i + 16..i + 20].try_into().unwrap());
+            return Ok(cd_offset as u64);
+        }
+    }
+    Err(io::Error::new(
+        io::ErrorKind::InvalidData,
+        "not a zip archive (no end-of-central-directory record)",
+    ))
+}
+
+fn read_central_directory(file: &mut File, cd_offset: u64) -> io::Result<Vec<CentralDirEntry>> {
+    file.seek(SeekFrom::Start(cd_offset))?;
+    let mut entries = Vec::new();
+    loop {
+        let mut sig = [0u8; 4];
+        if file.read(&mut sig)? < 4 || u32::from_le_bytes(sig) != CENTRAL_DIR_SIGNATURE {
+            break;
+        }
+        let mut header = [0u8; 42];
+        file.read_exact(&mut header)?;
+        let method = u16::from_le_bytes(header[6..8].try_into().unwrap());
+        let compressed_size = u32::from_le_bytes(header[16..20].try_into().unwrap()) as u64;
+        let name_len = u16::from_le_bytes(header[24..26].try_into().unwrap()) as usize;
+        let extra_len = u16::from_le_bytes(header[26..28].try_into().unwrap()) as usize;
+        let comment_len = u16::from_le_bytes(header[28..30].try_into().unwrap()) as usize;
+        let local_header_offset = u32::from_le_bytes(header[38..42].try_into().unwrap()) as u64;
+
+        let mut name_buf = vec![0u8; name_len];
+        file.read_exact(&mut name_buf)?;
+        let name = String::from_utf8_lossy(&name_buf).into_owned();
+        file.seek(SeekFrom::Current((extra_len + comment_len) as i64))?;
+
+        entries.push(CentralDirEntry {
+            name,
+            local_header_offset,
+            compressed_size,
+            method,
+        });
+    }
+    Ok(entries)
+}
+
+fn local_file_data_offset(file: &mut File, local_header_offset: u64) -> io::Result<u64> {
+    file.seek(SeekFrom::Start(local_header_offset))?;
+    let mut header = [0u8; 30];
+    file.read_exact(&mut header)?;
+    if u32::from_le_bytes(header[0..4].try_into().unwrap()) != LOCAL_FILE_SIGNATURE {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "corrupt zip local file header",
+        ));
+    }
+    let name_len = u16::from_le_bytes(header[26..28].try_into().unwrap()) as u64;
+    let extra_len = u16::from_le_bytes(header[28..30].try_into().unwrap()) as u64;
+    Ok(local_header_offset + 30 + name_len + extra_len)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Hand-build a minimal `stored`-only zip: one local file header + data per
+    /// member, followed by the matching central directory and EOCD record. This is
+    /// exactly the shape the stdlib `zipapp` module produces with its default
+    /// `compressed=False`.
+    fn build_zip(members: &[(&str, &[u8])], method: u16) -> Vec<u8> {
+        let mut out = Vec::new();
+        let mut central = Vec::new();
+
+        for (name, data) in members {
+            let local_header_offset = out.len() as u32;
+            out.extend_from_slice(&LOCAL_FILE_SIGNATURE.to_le_bytes());
+            out.extend_from_slice(&20u16.to_le_bytes()); // version needed
+            out.extend_from_slice(&0u16.to_le_bytes()); // flags
+            out.extend_from_slice(&method.to_le_bytes());
+            out.extend_from_slice(&0u16.to_le_bytes()); // mod time
+            out.extend_from_slice(&0u16.to_le_bytes()); // mod date
+            out.extend_from_slice(&0u32.to_le_bytes()); // crc32
+            out.extend_from_slice(&(data.len() as u32).to_le_bytes()); // compressed size
+            out.extend_from_slice(&(data.len() as u32).to_le_bytes()); // uncompressed size
+            out.extend_from_slice(&(name.len() as u16).to_le_bytes());
+            out.extend_from_slice(&0u16.to_le_bytes()); // extra len
+            out.extend_from_slice(name.as_bytes());
+            out.extend_from_slice(data);
+
+            central.extend_from_slice(&CENTRAL_DIR_SIGNATURE.to_le_bytes());
+            central.extend_from_slice(&20u16.to_le_bytes()); // version made by
+            central.extend_from_slice(&20u16.to_le_bytes()); // version needed
+            central.extend_from_slice(&0u16.to_le_bytes()); // flags
+            central.extend_from_slice(&method.to_le_bytes());
+            central.extend_from_slice(&0u16.to_le_bytes()); // mod time
+            central.extend_from_slice(&0u16.to_le_bytes()); // mod date
+            central.extend_from_slice(&0u32.to_le_bytes()); // crc32
+            central.extend_from_slice(&(data.len() as u32).to_le_bytes()); // compressed size
+            central.extend_from_slice(&(data.len() as u32).to_le_bytes()); // uncompressed size
+            central.extend_from_slice(&(name.len() as u16).to_le_bytes());
+            central.extend_from_slice(&0u16.to_le_bytes()); // extra len
+            central.extend_from_slice(&0u16.to_le_bytes()); // comment len
+            central.extend_from_slice(&0u16.to_le_bytes()); // disk number start
+            central.extend_from_slice(&0u16.to_le_bytes()); // internal attrs
+            central.extend_from_slice(&0u32.to_le_bytes()); // external attrs
+            central.extend_from_slice(&local_header_offset.to_le_bytes());
+            central.extend_from_slice(name.as_bytes());
+        }
+
+        let cd_offset = out.len() as u32;
+        let cd_size = central.len() as u32;
+        out.extend_from_slice(&central);
+
+        out.extend_from_slice(&EOCD_SIGNATURE.to_le_bytes());
+        out.extend_from_slice(&0u16.to_le_bytes()); // disk number
+        out.extend_from_slice(&0u16.to_le_bytes()); // disk with central dir
+        out.extend_from_slice(&(members.len() as u16).to_le_bytes()); // entries on this disk
+        out.extend_from_slice(&(members.len() as u16).to_le_bytes()); // total entries
+        out.extend_from_slice(&cd_size.to_le_bytes());
+        out.extend_from_slice(&cd_offset.to_le_bytes());
+        out.extend_from_slice(&0u16.to_le_bytes()); // comment len
+        out
+    }
+
+    fn write_temp_zip(name: &str, bytes: &[u8]) -> PathBuf {
+        let path = std::env::temp_dir().join(format!(
+            "rustpython_zip_importer_test_{}_{name}",
+            std::process::id()
+        ));
+        std::fs::write(&path, bytes).unwrap();
+        path
+    }
+
+    #[test]
+    fn reads_back_a_stored_member() {
+        let zip = build_zip(&[("pkg/__init__.py", b"x = 1\n")], METHOD_STORED);
+        let path = write_temp_zip("basic.zip", &zip);
+        let archive = ZipArchive::open(&path).unwrap();
+
+        assert!(archive.contains("pkg/__init__.py"));
+        assert!(!archive.contains("pkg/missing.py"));
+        assert_eq!(archive.read_member("pkg/__init__.py").unwrap(), b"x = 1\n");
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn resolves_module_source_for_a_plain_module_and_a_package() {
+        let zip = build_zip(
+            &[("foo/bar.py", b"1"), ("foo/baz/__init__.py", b"2")],
+            METHOD_STORED,
+        );
+        let path = write_temp_zip("modules.zip", &zip);
+        let archive = ZipArchive::open(&path).unwrap();
+
+        assert_eq!(
+            archive.module_source("foo.bar"),
+            Some("foo/bar.py".to_owned())
+        );
+        assert_eq!(
+            archive.module_source("foo.baz"),
+            Some("foo/baz/__init__.py".to_owned())
+        );
+        assert_eq!(archive.module_source("foo.nope"), None);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn reading_a_deflated_member_is_reported_as_unsupported() {
+        let zip = build_zip(&[("a.py", b"1")], /* deflate */ 8);
+        let path = write_temp_zip("deflated.zip", &zip);
+        let archive = ZipArchive::open(&path).unwrap();
+
+        let err = archive.read_member("a.py").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::Unsupported);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn open_rejects_a_non_zip_file() {
+        let path = write_temp_zip("not_a_zip.bin", b"definitely not a zip");
+        assert!(ZipArchive::open(&path).is_err());
+        std::fs::remove_file(&path).unwrap();
+    }
+}